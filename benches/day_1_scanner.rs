@@ -0,0 +1,88 @@
+//! Compares the single-pass digit scanner of day 1 to rewriting the spelled-out digits first.
+//!
+//! Runs on the cached input of day 1 when there is one, on a generated input otherwise:
+//! `cargo bench --bench day_1_scanner`.
+
+use std::fs;
+
+use advent_of_code_2023::{
+    bench::{measure, Timings},
+    day_1::{
+        get_computed_sum, get_replaced_spelled_out_digits, get_scanned_sum, DigitScanner,
+        Vocabulary,
+    },
+    runner::default_input_file,
+};
+
+const ITERATIONS: usize = 20;
+
+/// Lines mixing digits, spelled-out digits, overlapping words and noise, without any randomness
+/// so that every run measures the same input.
+fn get_generated_input(number_of_lines: usize) -> String {
+    let pieces = [
+        "two",
+        "1",
+        "xtwone",
+        "eightwo",
+        "abc",
+        "sevenine",
+        "7",
+        "qrst",
+        "four",
+        "nineight",
+        "z",
+        "threeight",
+        "5",
+        "oneight",
+    ];
+    let mut input = String::new();
+    for line_index in 0..number_of_lines {
+        for piece_index in 0..8 {
+            input.push_str(pieces[(line_index * 7 + piece_index * 3) % pieces.len()]);
+        }
+        input.push('\n');
+    }
+    return input;
+}
+
+fn print_timings(name: &str, timings: &Timings) {
+    println!(
+        "{:<12} | {:>12} | {:>12} | {:>12}",
+        name,
+        format!("{:.3?}", timings.min),
+        format!("{:.3?}", timings.median),
+        format!("{:.3?}", timings.max)
+    );
+}
+
+fn main() {
+    let input =
+        fs::read_to_string(default_input_file(1)).unwrap_or_else(|_| get_generated_input(100_000));
+    let vocabulary = Vocabulary::english();
+    let scanner = DigitScanner::new(&vocabulary);
+
+    let replaced_sum = get_computed_sum(&get_replaced_spelled_out_digits(&input, &vocabulary));
+    let scanned_sum = get_scanned_sum(&input, &scanner).unwrap() as i64;
+    assert_eq!(replaced_sum, scanned_sum, "both strategies must agree");
+
+    let replacing = measure(ITERATIONS, || {
+        return Ok(get_computed_sum(&get_replaced_spelled_out_digits(
+            &input,
+            &vocabulary,
+        )));
+    })
+    .unwrap();
+    let scanning = measure(ITERATIONS, || get_scanned_sum(&input, &scanner)).unwrap();
+
+    println!(
+        "Day 1 part 2 over {} lines, {ITERATIONS} iterations",
+        input.lines().count()
+    );
+    println!(
+        "{:<12} | {:>12} | {:>12} | {:>12}",
+        "Strategy", "Min", "Median", "Max"
+    );
+    println!("{:-<12}-+-{:-<12}-+-{:-<12}-+-{:-<12}", "", "", "", "");
+    print_timings("replace", &replacing);
+    print_timings("scan", &scanning);
+}
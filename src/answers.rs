@@ -0,0 +1,172 @@
+//! Accepted answers of the puzzles, used to check that a refactoring did not change any result.
+//!
+//! The answers file is a small subset of TOML, with one table per day:
+//!
+//! ```toml
+//! [day_7]
+//! part_1 = "6440"
+//! part_2 = 5905
+//! ```
+//!
+//! `answers.example.toml` at the root of the repository can be copied as a starting point.
+
+use std::collections::HashMap;
+
+use crate::{
+    error::{Error, Result},
+    runner::read_input,
+};
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+pub struct Answers {
+    answers: HashMap<(u8, u8), String>,
+}
+
+impl Answers {
+    pub fn load(answers_file: &str) -> Result<Answers> {
+        let text = read_input(answers_file)?;
+        return Answers::parse(&text).map_err(|(line, message)| Error::Answers {
+            path: answers_file.to_string(),
+            line,
+            message,
+        });
+    }
+
+    /// Parses the answers, an error is given as the line number and what went wrong on that line.
+    fn parse(text: &str) -> std::result::Result<Answers, (usize, String)> {
+        let mut answers = HashMap::new();
+        let mut current_day: Option<u8> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+                let day = table
+                    .trim()
+                    .strip_prefix("day_")
+                    .and_then(|day| day.parse::<u8>().ok())
+                    .ok_or_else(|| {
+                        (
+                            line_number,
+                            format!("expected a `[day_<N>]` table, found {line:?}"),
+                        )
+                    })?;
+                current_day = Some(day);
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err((
+                    line_number,
+                    format!("expected `part_<N> = <answer>`, found {line:?}"),
+                ));
+            };
+            let Some(day) = current_day else {
+                return Err((
+                    line_number,
+                    "answers must be inside a `[day_<N>]` table".to_string(),
+                ));
+            };
+            let part = match key.trim() {
+                "part_1" => 1,
+                "part_2" => 2,
+                key => {
+                    return Err((
+                        line_number,
+                        format!("expected part_1 or part_2, found {key:?}"),
+                    ))
+                }
+            };
+
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|x| x.strip_suffix('"'))
+                .unwrap_or(value);
+            if answers.insert((day, part), value.to_string()).is_some() {
+                return Err((
+                    line_number,
+                    format!("part_{part} of day {day} already has an answer"),
+                ));
+            }
+        }
+
+        return Ok(Answers { answers });
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        return self.answers.get(&(day, part)).map(|answer| answer.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_error(text: &str) -> (usize, String) {
+        return Answers::parse(text).err().unwrap();
+    }
+
+    #[test]
+    fn parses_the_example_file() {
+        let answers = Answers::parse(include_str!("../answers.example.toml")).unwrap();
+        assert_eq!(answers.get(1, 2), Some("281"));
+        assert_eq!(answers.get(7, 1), Some("6440"));
+        assert_eq!(answers.get(3, 1), None);
+    }
+
+    #[test]
+    fn parses_quoted_and_bare_values() {
+        let answers = Answers::parse("[day_7]\npart_1 = \"6440\"\npart_2 = 5905\n").unwrap();
+        assert_eq!(answers.get(7, 1), Some("6440"));
+        assert_eq!(answers.get(7, 2), Some("5905"));
+    }
+
+    #[test]
+    fn ignores_comments_and_blank_lines() {
+        let text = "# Accepted answers\n\n[day_2] # cubes\n  part_1 = 8 # the example\n";
+        let answers = Answers::parse(text).unwrap();
+        assert_eq!(answers.get(2, 1), Some("8"));
+        assert_eq!(answers.get(2, 2), None);
+    }
+
+    #[test]
+    fn rejects_bad_tables() {
+        let (line, message) = get_error("[day_2]\npart_1 = 8\n[cubes]\n");
+        assert_eq!(line, 3);
+        assert_eq!(message, "expected a `[day_<N>]` table, found \"[cubes]\"");
+
+        let (line, _) = get_error("[day_x]\n");
+        assert_eq!(line, 1);
+    }
+
+    #[test]
+    fn rejects_keys_outside_a_table() {
+        let (line, message) = get_error("# Answers\npart_1 = 8\n");
+        assert_eq!(line, 2);
+        assert_eq!(message, "answers must be inside a `[day_<N>]` table");
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_lines() {
+        let (line, message) = get_error("[day_2]\npart_3 = 8\n");
+        assert_eq!(line, 2);
+        assert_eq!(message, "expected part_1 or part_2, found \"part_3\"");
+
+        let (line, message) = get_error("[day_2]\n8\n");
+        assert_eq!(line, 2);
+        assert_eq!(message, "expected `part_<N> = <answer>`, found \"8\"");
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        let (line, message) = get_error("[day_2]\npart_1 = 8\n\n[day_2]\npart_1 = 9\n");
+        assert_eq!(line, 5);
+        assert_eq!(message, "part_1 of day 2 already has an answer");
+    }
+}
@@ -0,0 +1,174 @@
+//! Timing of every stage of a day's solution, to compare implementations objectively.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::{Error, Result},
+    runner::{catch_panic, read_input},
+    solver::Day,
+};
+
+/// Spread of the durations measured over several iterations.
+#[derive(Debug, Clone, Copy)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    /// Summarises the samples, there must be at least one.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Timings {
+        samples.sort();
+        return Timings {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        };
+    }
+}
+
+/// Times `iterations` calls of `run`, stopping at the first error.
+pub fn measure<T>(iterations: usize, mut run: impl FnMut() -> Result<T>) -> Result<Timings> {
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        run()?;
+        samples.push(start.elapsed());
+    }
+    return Ok(Timings::from_samples(samples));
+}
+
+/// Times reading the input, parsing it, then solving each part, separately.
+///
+/// A panic of the solution is reported as an error, like when running the day.
+pub fn bench_day(
+    day: &Day,
+    input_file: &str,
+    iterations: usize,
+) -> Result<Vec<(&'static str, Timings)>> {
+    let reading = measure(iterations, || read_input(input_file))?;
+
+    let input = read_input(input_file)?;
+    let parsing = measure(iterations, || {
+        catch_panic(day.number, || (day.parse)(&input))
+    })?;
+
+    let solver = catch_panic(day.number, || (day.parse)(&input))?;
+    let part_1 = measure(iterations, || catch_panic(day.number, || solver.part_1()))?;
+    let part_2 = measure(iterations, || catch_panic(day.number, || solver.part_2()))?;
+
+    return Ok(vec![
+        ("read", reading),
+        ("parse", parsing),
+        ("part 1", part_1),
+        ("part 2", part_2),
+    ]);
+}
+
+/// Benchmarks the given days and prints a table of their timings.
+///
+/// Every day is timed even if another one fails, the failures are reported once all days are done.
+pub fn run_bench(days: &[(&Day, String)], iterations: usize) -> Result<()> {
+    let mut failures: Vec<Error> = Vec::new();
+
+    println!("Timings over {} iteration(s)", iterations.max(1));
+    println!(
+        "{:>3} | {:<6} | {:>12} | {:>12} | {:>12}",
+        "Day", "Stage", "Min", "Median", "Max"
+    );
+    println!(
+        "{:-<3}-+-{:-<6}-+-{:-<12}-+-{:-<12}-+-{:-<12}",
+        "", "", "", "", ""
+    );
+    for (day, input_file) in days {
+        let stages = match bench_day(day, input_file, iterations) {
+            Ok(stages) => stages,
+            Err(error) => {
+                println!(
+                    "{:>3} | {:<6} | {:>12} | {:>12} | {:>12}",
+                    day.number, "-", "FAILED", "-", "-"
+                );
+                failures.push(error);
+                continue;
+            }
+        };
+
+        for (stage, timings) in stages {
+            println!(
+                "{:>3} | {:<6} | {:>12} | {:>12} | {:>12}",
+                day.number,
+                stage,
+                format!("{:.3?}", timings.min),
+                format!("{:.3?}", timings.median),
+                format!("{:.3?}", timings.max)
+            );
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!();
+    for failure in failures.iter() {
+        eprintln!("{failure}");
+    }
+    return Err(Error::Unsolved {
+        failures: failures.len(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{self, Solver};
+
+    fn panicking_parse(_input: &str) -> Result<Box<dyn Solver>> {
+        panic!("this day does not parse anything");
+    }
+
+    #[test]
+    fn reports_a_panic_as_an_error() {
+        let day = Day {
+            number: 3,
+            parse: panicking_parse,
+        };
+        let error = bench_day(&day, "Cargo.toml", 1).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Day 3 panicked: this day does not parse anything"
+        );
+    }
+
+    #[test]
+    fn times_the_other_days_after_a_failure() {
+        let input_file =
+            std::env::temp_dir().join(format!("aoc_2023_bench_{}", std::process::id()));
+        std::fs::write(
+            &input_file,
+            "Time:      7  15   30\nDistance:  9  40  200\n",
+        )
+        .unwrap();
+
+        let panicking_day = Day {
+            number: 3,
+            parse: panicking_parse,
+        };
+        let days = [
+            (
+                solver::get_day(1).unwrap(),
+                "inputs/missing.txt".to_string(),
+            ),
+            (&panicking_day, "Cargo.toml".to_string()),
+            (
+                solver::get_day(6).unwrap(),
+                input_file.display().to_string(),
+            ),
+        ];
+        let result = run_bench(&days, 1);
+        std::fs::remove_file(&input_file).unwrap();
+
+        assert!(matches!(result, Err(Error::Unsolved { failures: 2 })));
+    }
+}
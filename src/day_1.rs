@@ -1,46 +1,422 @@
 use regex::Regex;
-use std::{collections::HashMap, fs};
+use std::{fmt, fs, io::BufRead};
 
-pub fn solve(input_file: String) {
-    println!("### Solving Day 1  puzzle...###");
+use crate::{
+    error::{input_lines, Error, InputLine, Result},
+    solver::{Answer, Solver},
+};
 
-    let input = fs::read_to_string(input_file).unwrap();
+pub struct CalibrationDocument {
+    pub input: String,
+    /// Spelled out digits recognised in part 2.
+    pub vocabulary: Vocabulary,
+}
+
+impl Solver for CalibrationDocument {
+    fn parse(input: &str) -> Result<Self> {
+        return Ok(CalibrationDocument {
+            input: input.to_string(),
+            vocabulary: Vocabulary::english(),
+        });
+    }
+
+    fn part_1(&self) -> Result<Answer> {
+        return Ok(part_1(&self.input)?.into());
+    }
+
+    fn part_2(&self) -> Result<Answer> {
+        return Ok(part_2(&self.input, &self.vocabulary)?.into());
+    }
+}
+
+/// Words that spell out a digit, e.g. "one" for 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u8)>,
+}
+
+impl Vocabulary {
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u8)>) -> Vocabulary {
+        return Vocabulary {
+            words: words
+                .into_iter()
+                .map(|(word, digit)| (word.to_string(), digit))
+                .collect(),
+        };
+    }
+
+    pub fn english() -> Vocabulary {
+        return Vocabulary::new([
+            ("one", 1),
+            ("two", 2),
+            ("three", 3),
+            ("four", 4),
+            ("five", 5),
+            ("six", 6),
+            ("seven", 7),
+            ("eight", 8),
+            ("nine", 9),
+        ]);
+    }
+
+    pub fn french() -> Vocabulary {
+        return Vocabulary::new([
+            ("un", 1),
+            ("deux", 2),
+            ("trois", 3),
+            ("quatre", 4),
+            ("cinq", 5),
+            ("six", 6),
+            ("sept", 7),
+            ("huit", 8),
+            ("neuf", 9),
+        ]);
+    }
+
+    pub fn german() -> Vocabulary {
+        return Vocabulary::new([
+            ("eins", 1),
+            ("zwei", 2),
+            ("drei", 3),
+            ("vier", 4),
+            ("fünf", 5),
+            ("sechs", 6),
+            ("sieben", 7),
+            ("acht", 8),
+            ("neun", 9),
+        ]);
+    }
+
+    /// Built-in vocabulary of that name, `english`, `french` or `german`, or else the vocabulary
+    /// read from that file.
+    pub fn load(name_or_file: &str) -> Result<Vocabulary> {
+        return match name_or_file {
+            "english" => Ok(Vocabulary::english()),
+            "french" => Ok(Vocabulary::french()),
+            "german" => Ok(Vocabulary::german()),
+            vocabulary_file => Vocabulary::from_file(vocabulary_file),
+        };
+    }
+
+    /// Reads a vocabulary with one `word = digit` per line, blank lines and `#` comments are ignored.
+    pub fn from_file(vocabulary_file: &str) -> Result<Vocabulary> {
+        let text = fs::read_to_string(vocabulary_file).map_err(|source| Error::Io {
+            path: vocabulary_file.to_string(),
+            source,
+        })?;
+        return Vocabulary::parse(&text);
+    }
+
+    pub fn parse(text: &str) -> Result<Vocabulary> {
+        let mut words: Vec<(String, u8)> = Vec::new();
+        for line in input_lines(1, text) {
+            let definition = line.text.split('#').next().unwrap_or_default().trim();
+            if definition.is_empty() {
+                continue;
+            }
+
+            let Some((word, digit)) = definition.split_once('=') else {
+                return Err(line.error(definition, "expected `<word> = <digit>`"));
+            };
+            let (word, digit) = (word.trim(), digit.trim());
+            if word.is_empty() || word.chars().any(|character| character.is_ascii_digit()) {
+                return Err(line.error(word, "expected a word without digits"));
+            }
+            let digit = line.parse::<u8>(digit, "a digit")?;
+            if digit > 9 {
+                return Err(line.error(definition, "expected a single digit"));
+            }
+
+            words.push((word.to_string(), digit));
+        }
+        return Ok(Vocabulary { words });
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u8)> {
+        return self
+            .words
+            .iter()
+            .map(|(word, digit)| (word.as_str(), *digit));
+    }
+}
+
+/// A digit found in a line, either written as a digit or spelled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitMatch {
+    pub digit: u8,
+    /// Byte offset of the first character of the digit in the line.
+    pub start: usize,
+    /// Byte offset just after the last character of the digit in the line.
+    pub end: usize,
+}
+
+/// Finds the first and last digits of a line without rewriting it.
+///
+/// The words of the vocabulary are stored in a trie, so that every position of the line is
+/// checked against all the words at once. Each position is checked on its own, so overlapping
+/// words such as "twone" are found by construction.
+pub struct DigitScanner {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: Vec<(u8, usize)>,
+    digit: Option<u8>,
+}
+
+impl DigitScanner {
+    pub fn new(vocabulary: &Vocabulary) -> DigitScanner {
+        let mut nodes = vec![TrieNode::default()];
+        for (word, digit) in vocabulary.words() {
+            let mut current_node = 0;
+            for byte in word.bytes() {
+                let child = nodes[current_node]
+                    .children
+                    .iter()
+                    .find(|(child_byte, _)| *child_byte == byte)
+                    .map(|(_, child)| *child);
+                current_node = match child {
+                    Some(child) => child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[current_node].children.push((byte, child));
+                        child
+                    }
+                };
+            }
+            // The first definition of a word wins, like the first replacement did.
+            nodes[current_node].digit.get_or_insert(digit);
+        }
+        return DigitScanner { nodes };
+    }
 
-    println!("Part 1 Result: {:?}", part_1(input.to_owned()));
-    println!("Part 2 Result: {:?}", part_2(input.to_owned()));
+    /// Digit starting at byte `start` of the line. When several words start there, the shortest wins.
+    fn digit_at(&self, line: &[u8], start: usize) -> Option<DigitMatch> {
+        if line[start].is_ascii_digit() {
+            return Some(DigitMatch {
+                digit: line[start] - b'0',
+                start,
+                end: start + 1,
+            });
+        }
+
+        let mut current_node = 0;
+        for (offset, byte) in line[start..].iter().enumerate() {
+            let child = self.nodes[current_node]
+                .children
+                .iter()
+                .find(|(child_byte, _)| child_byte == byte)?;
+            current_node = child.1;
+
+            if let Some(digit) = self.nodes[current_node].digit {
+                return Some(DigitMatch {
+                    digit,
+                    start,
+                    end: start + offset + 1,
+                });
+            }
+        }
+        return None;
+    }
+
+    pub fn first_digit(&self, line: &str) -> Option<DigitMatch> {
+        let line = line.as_bytes();
+        return (0..line.len()).find_map(|start| self.digit_at(line, start));
+    }
+
+    pub fn last_digit(&self, line: &str) -> Option<DigitMatch> {
+        let line = line.as_bytes();
+        return (0..line.len())
+            .rev()
+            .find_map(|start| self.digit_at(line, start));
+    }
+
+    /// The two-digit number made of the first and last digits of the line, if it has any digit.
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        let first_digit = self.first_digit(line)?;
+        let last_digit = self.last_digit(line)?;
+        return Some(first_digit.digit as u32 * 10 + last_digit.digit as u32);
+    }
+}
+
+/// How one line of the document contributes to the calibration sum.
+#[derive(Debug, Clone, Copy)]
+pub struct LineExplanation<'a> {
+    pub line: InputLine<'a>,
+    /// First and last digits of the line, `None` if it has no digit and adds nothing to the sum.
+    pub digits: Option<(DigitMatch, DigitMatch)>,
+}
+
+impl LineExplanation<'_> {
+    pub fn value(&self) -> u32 {
+        return match self.digits {
+            Some((first_digit, last_digit)) => {
+                first_digit.digit as u32 * 10 + last_digit.digit as u32
+            }
+            None => 0,
+        };
+    }
+}
+
+impl fmt::Display for LineExplanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {:?}", self.line.number, self.line.text)?;
+        let Some((first_digit, last_digit)) = self.digits else {
+            return write!(f, " has no digit --> 0");
+        };
+        for (name, digit) in [("first", first_digit), ("last", last_digit)] {
+            write!(
+                f,
+                ", {name} {:?} at {}..{}",
+                &self.line.text[digit.start..digit.end],
+                digit.start,
+                digit.end
+            )?;
+        }
+        write!(f, " --> {}", self.value())
+    }
 }
 
-fn part_1(input: String) -> i32 {
-    return get_computed_sum(input);
+/// Explains line by line how the calibration sum is computed, only keeping the lines matching `filter`.
+///
+/// The sum of the values of all the lines is the one of `get_computed_sum` when the scanner has no
+/// word, and the one of `get_scanned_sum` otherwise.
+pub fn get_explained_lines<'a>(
+    input: &'a str,
+    scanner: &DigitScanner,
+    filter: Option<&Regex>,
+) -> Vec<LineExplanation<'a>> {
+    return input_lines(1, input)
+        .filter(|line| filter.is_none_or(|filter| filter.is_match(line.text)))
+        .map(|line| LineExplanation {
+            line,
+            digits: scanner
+                .first_digit(line.text)
+                .zip(scanner.last_digit(line.text)),
+        })
+        .collect();
 }
 
-fn part_2(input: String) -> i32 {
-    return get_computed_sum(get_replaced_spelled_out_digits(input));
+fn part_1(input: &str) -> Result<u64> {
+    return get_scanned_sum(input, &DigitScanner::new(&Vocabulary::new([])));
 }
 
-fn get_computed_sum(input: String) -> i32 {
+fn part_2(input: &str, vocabulary: &Vocabulary) -> Result<u64> {
+    return get_scanned_sum(input, &DigitScanner::new(vocabulary));
+}
+
+/// Same result as `get_computed_sum` on the output of `get_replaced_spelled_out_digits`,
+/// but the input is scanned as is instead of being rewritten first.
+pub fn get_scanned_sum(input: &str, scanner: &DigitScanner) -> Result<u64> {
+    return get_streamed_sum(input.as_bytes(), "the document", scanner);
+}
+
+/// Calibration sum of a document read line by line from `reader`, e.g. a file, the standard
+/// input or a decompressed stream. `path` names the document in errors.
+///
+/// Only the current line is kept in memory, so the document can be arbitrarily large. The sum
+/// is checked, and an error is returned rather than a wrong sum if it does not fit in 64 bits.
+pub fn get_streamed_sum(
+    mut reader: impl BufRead,
+    path: &str,
+    scanner: &DigitScanner,
+) -> Result<u64> {
+    let mut total_sum: u64 = 0;
+    let mut line = String::new();
+    let mut line_number = 0;
+
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|source| Error::ReadLine {
+                path: path.to_string(),
+                line: line_number + 1,
+                source,
+            })?;
+        if read == 0 {
+            return Ok(total_sum);
+        }
+        line_number += 1;
+
+        let line = line.trim_end_matches(['\n', '\r']);
+        if let Some(number) = scanner.calibration_value(line) {
+            total_sum = total_sum
+                .checked_add(number as u64)
+                .ok_or_else(|| Error::Puzzle {
+                    day: 1,
+                    message: format!("the calibration sum overflows at line {line_number}"),
+                })?;
+        }
+    }
+}
+
+/// Reference calibration value of a line, which checks every starting index for a digit or a
+/// word of the vocabulary. It is slow, but simple enough to be obviously right, so that cleverer
+/// implementations can be checked against it.
+pub fn get_brute_force_calibration_value(line: &str, vocabulary: &Vocabulary) -> Option<u32> {
+    let mut digits = Vec::new();
+
+    for start in 0..line.len() {
+        let rest = &line.as_bytes()[start..];
+        if rest[0].is_ascii_digit() {
+            digits.push(rest[0] - b'0');
+            continue;
+        }
+
+        // When several words start at the same index, the shortest one is the digit.
+        let mut shortest_word: Option<(usize, u8)> = None;
+        for (word, digit) in vocabulary.words() {
+            let is_shorter = shortest_word.is_none_or(|(length, _)| word.len() < length);
+            if rest.starts_with(word.as_bytes()) && is_shorter {
+                shortest_word = Some((word.len(), digit));
+            }
+        }
+        if let Some((_, digit)) = shortest_word {
+            digits.push(digit);
+        }
+    }
+
+    let first_digit = *digits.first()? as u32;
+    let last_digit = *digits.last()? as u32;
+    return Some(first_digit * 10 + last_digit);
+}
+
+pub fn get_brute_force_sum(input: &str, vocabulary: &Vocabulary) -> u64 {
+    let mut total_sum: u64 = 0;
+
+    for line in input.lines() {
+        if let Some(number) = get_brute_force_calibration_value(line, vocabulary) {
+            total_sum += number as u64;
+        }
+    }
+
+    return total_sum;
+}
+
+pub fn get_computed_sum(input: &str) -> i64 {
     let re = Regex::new(r"\d").unwrap();
-    let mut total_sum: i32 = 0;
+    let mut total_sum: i64 = 0;
 
     for line in input.lines() {
         let matches: Vec<_> = re.find_iter(line).map(|numbers| numbers.as_str()).collect();
 
-        if matches.len() == 0 {
+        if matches.is_empty() {
             continue;
         }
 
         let mut number = matches[0].to_string();
 
-        number = number + matches[matches.len() - 1];
+        number += matches[matches.len() - 1];
 
-        // println!("{line} --> {number}");
-
-        total_sum += number.parse::<i32>().unwrap();
+        total_sum += number.parse::<i64>().unwrap();
     }
 
     return total_sum;
 }
-fn get_replaced_spelled_out_digits(input: String) -> String {
+pub fn get_replaced_spelled_out_digits(input: &str, vocabulary: &Vocabulary) -> String {
     // This replaces every occurrence of a spelled out digit into
     // the number, wrapped with the spelled out version on both sides
     //
@@ -56,31 +432,302 @@ fn get_replaced_spelled_out_digits(input: String) -> String {
     // digit in the middle will make sure that the last digit at
     // the end is the correct one.
     // "3twoone" --> "3two2twone1one" --> 31
-    let name_to_int = HashMap::from([
-        ("one", "1"),
-        ("two", "2"),
-        ("three", "3"),
-        ("four", "4"),
-        ("five", "5"),
-        ("si", "6"),
-        ("seven", "7"),
-        ("eight", "8"),
-        ("nine", "9"),
-    ]);
-
-    let mut as_letter_to_replacement_pattern = HashMap::new();
-
-    for (as_letter, as_value) in name_to_int {
-        as_letter_to_replacement_pattern.insert(
-            as_letter.to_string(),
-            as_letter.to_owned() + as_value + as_letter,
+    let mut sanitized_input = input.to_owned();
+    for (as_letter, as_value) in vocabulary.words() {
+        let replacement_pattern = format!("{as_letter}{as_value}{as_letter}");
+        sanitized_input = sanitized_input.replace(as_letter, &replacement_pattern);
+    }
+
+    return sanitized_input;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use super::*;
+    use crate::random::Random;
+
+    const EXAMPLE_PART_1: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    const EXAMPLE_PART_2: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    /// Documents in the style of the part 2 example, with overlapping words of each language.
+    const FRENCH_DOCUMENT: &str = "deux1neuf
+huitroisquatre
+cinqunsix
+xseptroisept7
+neufhuit
+sixx
+";
+    const FRENCH_SUM: i64 = 29 + 84 + 56 + 77 + 98 + 66;
+
+    const GERMAN_DOCUMENT: &str = "zwei1neun
+achtdreivier
+fünfeinsechs
+xsiebeneinsieben2
+neunacht
+vierr
+";
+    const GERMAN_SUM: i64 = 29 + 84 + 56 + 72 + 98 + 44;
+
+    #[test]
+    fn solves_part_1_example() {
+        let document = CalibrationDocument::parse(EXAMPLE_PART_1).unwrap();
+        assert_eq!(document.part_1().unwrap(), Answer::from(142));
+    }
+
+    #[test]
+    fn solves_part_2_example() {
+        let document = CalibrationDocument::parse(EXAMPLE_PART_2).unwrap();
+        assert_eq!(document.part_2().unwrap(), Answer::from(281));
+    }
+
+    #[test]
+    fn computes_calibration_value_of_each_line() {
+        let values: Vec<i64> = EXAMPLE_PART_1.lines().map(get_computed_sum).collect();
+        assert_eq!(values, vec![12, 38, 15, 77]);
+    }
+
+    #[test]
+    fn keeps_overlapping_spelled_out_digits() {
+        // The last digit of "3twoone" is "one", even though it overlaps with "two".
+        for (line, value) in [("3twoone", 31), ("eightwo", 82), ("7pqrstsixteen", 76)] {
+            let sanitized_line = get_replaced_spelled_out_digits(line, &Vocabulary::english());
+            assert_eq!(get_computed_sum(&sanitized_line), value);
+        }
+    }
+
+    #[test]
+    fn reads_other_languages() {
+        let french_input = get_replaced_spelled_out_digits(FRENCH_DOCUMENT, &Vocabulary::french());
+        let german_input = get_replaced_spelled_out_digits(GERMAN_DOCUMENT, &Vocabulary::german());
+
+        assert_eq!(get_computed_sum(&french_input), FRENCH_SUM);
+        assert_eq!(get_computed_sum(&german_input), GERMAN_SUM);
+    }
+
+    #[test]
+    fn loads_vocabularies_by_name_or_file() {
+        assert_eq!(Vocabulary::load("french").unwrap(), Vocabulary::french());
+
+        let vocabulary_file =
+            std::env::temp_dir().join(format!("aoc_2023_vocabulary_{}", std::process::id()));
+        fs::write(&vocabulary_file, "# Roman numerals\ni = 1\nv = 5\n").unwrap();
+        let vocabulary = Vocabulary::from_file(&vocabulary_file.display().to_string());
+        let loaded_vocabulary = Vocabulary::load(&vocabulary_file.display().to_string());
+        fs::remove_file(&vocabulary_file).unwrap();
+
+        assert_eq!(vocabulary.unwrap(), Vocabulary::new([("i", 1), ("v", 5)]));
+        assert_eq!(
+            loaded_vocabulary.unwrap(),
+            Vocabulary::new([("i", 1), ("v", 5)])
         );
+        assert!(matches!(Vocabulary::load("klingon"), Err(Error::Io { .. })));
     }
 
-    let mut sanitized_input = input.to_owned();
-    for (from, to) in &as_letter_to_replacement_pattern {
-        sanitized_input = sanitized_input.replace(from, to);
+    #[test]
+    fn parses_custom_vocabulary() {
+        let vocabulary = Vocabulary::parse("# Roman numerals\ni = 1\n\nv = 5 # five\n").unwrap();
+        assert_eq!(vocabulary, Vocabulary::new([("i", 1), ("v", 5)]));
+
+        let input = get_replaced_spelled_out_digits("xvix", &vocabulary);
+        assert_eq!(get_computed_sum(&input), 51);
     }
 
-    return sanitized_input;
+    #[test]
+    fn rejects_malformed_vocabulary() {
+        assert!(matches!(
+            Vocabulary::parse("one = 1\ntwo 2\n"),
+            Err(Error::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            Vocabulary::parse("ten = 10\n"),
+            Err(Error::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn scans_overlapping_digits() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+
+        let first_digit = scanner.first_digit("xtwone3four").unwrap();
+        let last_digit = scanner.last_digit("xtwone3four").unwrap();
+        assert_eq!(
+            (first_digit.digit, first_digit.start, first_digit.end),
+            (2, 1, 4)
+        );
+        assert_eq!(
+            (last_digit.digit, last_digit.start, last_digit.end),
+            (4, 7, 11)
+        );
+
+        for (line, value) in [
+            ("3twoone", 31),
+            ("eightwo", 82),
+            ("sevenine", 79),
+            ("abc", 0),
+        ] {
+            assert_eq!(scanner.calibration_value(line).unwrap_or_default(), value);
+        }
+    }
+
+    #[test]
+    fn scans_like_the_replacement_strategy() {
+        for (vocabulary, document, expected_sum) in [
+            (Vocabulary::english(), EXAMPLE_PART_2, 281),
+            (Vocabulary::french(), FRENCH_DOCUMENT, FRENCH_SUM),
+            (Vocabulary::german(), GERMAN_DOCUMENT, GERMAN_SUM),
+        ] {
+            let scanner = DigitScanner::new(&vocabulary);
+            let sanitized_input = get_replaced_spelled_out_digits(document, &vocabulary);
+            assert_eq!(get_computed_sum(&sanitized_input), expected_sum);
+            assert_eq!(
+                get_scanned_sum(document, &scanner).unwrap() as i64,
+                expected_sum
+            );
+        }
+    }
+
+    #[test]
+    fn explains_each_line() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+        let explanations = get_explained_lines(EXAMPLE_PART_2, &scanner, None);
+
+        let total: u32 = explanations
+            .iter()
+            .map(|explanation| explanation.value())
+            .sum();
+        assert_eq!(
+            total as u64,
+            get_scanned_sum(EXAMPLE_PART_2, &scanner).unwrap()
+        );
+        assert_eq!(
+            explanations[1].to_string(),
+            r#"line 2: "eightwothree", first "eight" at 0..5, last "three" at 7..12 --> 83"#
+        );
+
+        let digits_only = DigitScanner::new(&Vocabulary::new([]));
+        let total: u32 = get_explained_lines(EXAMPLE_PART_1, &digits_only, None)
+            .iter()
+            .map(|explanation| explanation.value())
+            .sum();
+        assert_eq!(total as i64, get_computed_sum(EXAMPLE_PART_1));
+    }
+
+    #[test]
+    fn explains_only_the_filtered_lines() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+        let filter = Regex::new("^[0-9]").unwrap();
+        let explanations = get_explained_lines(EXAMPLE_PART_2, &scanner, Some(&filter));
+
+        let line_numbers: Vec<usize> = explanations
+            .iter()
+            .map(|explanation| explanation.line.number)
+            .collect();
+        assert_eq!(line_numbers, vec![5, 7]);
+        assert_eq!(
+            explanations[0].to_string(),
+            r#"line 5: "4nineeightseven2", first "4" at 0..1, last "2" at 15..16 --> 42"#
+        );
+    }
+
+    #[test]
+    fn streams_the_document_line_by_line() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+        // A tiny buffer makes lines span several reads.
+        let windows_input = EXAMPLE_PART_2.replace('\n', "\r\n");
+        let reader = BufReader::with_capacity(4, windows_input.as_bytes());
+        assert_eq!(get_streamed_sum(reader, "example", &scanner).unwrap(), 281);
+
+        let invalid_utf8: &[u8] = b"two1nine\n\xff\n";
+        let error = get_streamed_sum(invalid_utf8, "example", &scanner).unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::ReadLine { path, line: 2, .. } if path == "example"
+        ));
+        assert!(error
+            .to_string()
+            .starts_with("example, line 2 could not be read: "));
+    }
+
+    fn pick<'a>(random: &mut Random, choices: &[&'a str]) -> &'a str {
+        return choices[random.next_between(0, choices.len() as u64 - 1) as usize];
+    }
+
+    /// Random lines of letters, digits, words of the vocabulary and overlapping words.
+    fn get_random_document(random: &mut Random, vocabulary: &Vocabulary) -> String {
+        let words: Vec<&str> = vocabulary.words().map(|(word, _)| word).collect();
+        let overlapping_words = [
+            "twone",
+            "eightwo",
+            "sevenine",
+            "oneight",
+            "threeight",
+            "nineight",
+        ];
+
+        let mut document = String::new();
+        for _ in 0..200 {
+            for _ in 0..random.next_between(0, 11) {
+                match random.next_between(0, 3) {
+                    0 => document.push((b'a' + random.next_between(0, 25) as u8) as char),
+                    1 => document.push((b'0' + random.next_between(0, 9) as u8) as char),
+                    2 => document.push_str(pick(random, &words)),
+                    _ => document.push_str(pick(random, &overlapping_words)),
+                }
+            }
+            document.push('\n');
+        }
+        return document;
+    }
+
+    #[test]
+    fn agrees_with_the_brute_force_reference() {
+        let mut random = Random::new(0x2023_1201);
+        let english = Vocabulary::english();
+
+        for _ in 0..100 {
+            let document = get_random_document(&mut random, &english);
+            let expected_sum = get_brute_force_sum(&document, &english);
+
+            let sanitized_document = get_replaced_spelled_out_digits(&document, &english);
+            assert_eq!(
+                get_computed_sum(&sanitized_document) as u64,
+                expected_sum,
+                "{document}"
+            );
+            let scanner = DigitScanner::new(&english);
+            assert_eq!(
+                get_scanned_sum(&document, &scanner).unwrap(),
+                expected_sum,
+                "{document}"
+            );
+        }
+
+        for vocabulary in [Vocabulary::french(), Vocabulary::german()] {
+            let scanner = DigitScanner::new(&vocabulary);
+            for _ in 0..20 {
+                let document = get_random_document(&mut random, &vocabulary);
+                for line in document.lines() {
+                    assert_eq!(
+                        scanner.calibration_value(line),
+                        get_brute_force_calibration_value(line, &vocabulary),
+                        "{line}"
+                    );
+                }
+            }
+        }
+    }
 }
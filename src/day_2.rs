@@ -1,151 +1,1202 @@
-use std::{cmp::max, collections::HashSet, fs};
+use std::{
+    cmp::max,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    str::FromStr,
+};
 
-use regex::Regex;
+use crate::{
+    error::{input_lines, Error, InputLine, Result},
+    json::JsonObject,
+    random::Random,
+    solver::{Answer, Solver},
+};
 
-pub fn solve(input_file: String) {
-    println!("### Solving Day 2  puzzle...###");
+/// The bag the Elf loaded for part 1.
+pub fn get_current_configuration() -> Configuration {
+    return Configuration::new([("red", 12), ("green", 13), ("blue", 14)]);
+}
+
+/// A game: the Elf reveals cubes from the bag several times, putting them back in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: i32,
+    /// Sets of cubes revealed during the game, in order.
+    pub draws: Vec<Configuration>,
+}
+
+/// Every game of the input.
+pub struct CubeGames {
+    pub games: Vec<Game>,
+}
 
-    let input = fs::read_to_string(input_file).unwrap();
+impl Solver for CubeGames {
+    fn parse(input: &str) -> Result<Self> {
+        return Ok(CubeGames {
+            games: get_games(input)?,
+        });
+    }
 
-    let current_configuration = Configuration {
-        nb_reds: 12,
-        nb_blues: 14,
-        nb_greens: 13,
+    fn part_1(&self) -> Result<Answer> {
+        let game_reports = part_1(&self.games, &get_current_configuration());
+        let mut total_identifier_sum: i32 = 0;
+        for report in game_reports.iter().filter(|report| report.is_valid()) {
+            total_identifier_sum =
+                total_identifier_sum
+                    .checked_add(report.id)
+                    .ok_or_else(|| Error::Puzzle {
+                        day: 2,
+                        message: format!(
+                            "the sum of the identifiers overflows at game {}",
+                            report.id
+                        ),
+                    })?;
+        }
+        return Ok(total_identifier_sum.into());
+    }
+
+    fn part_2(&self) -> Result<Answer> {
+        let mut total_cube_power_sum: i64 = 0;
+        for (game, cube_power) in self.games.iter().zip(part_2(&self.games)) {
+            let Some(cube_power) = cube_power else {
+                return Err(Error::Puzzle {
+                    day: 2,
+                    message: format!("the power of game {} overflows 64-bit numbers", game.id),
+                });
+            };
+            total_cube_power_sum =
+                total_cube_power_sum
+                    .checked_add(cube_power)
+                    .ok_or_else(|| Error::Puzzle {
+                        day: 2,
+                        message: format!("the sum of the powers overflows at game {}", game.id),
+                    })?;
+        }
+        return Ok(total_cube_power_sum.into());
+    }
+}
+
+/// Parses every game of the input, which must have distinct identifiers.
+pub fn get_games(input: &str) -> Result<Vec<Game>> {
+    let mut games: Vec<Game> = Vec::new();
+    let mut line_numbers: HashMap<i32, usize> = HashMap::new();
+
+    for line in input_lines(2, input) {
+        let (game, identifier) = get_game(&line)?;
+        if let Some(line_number) = line_numbers.insert(game.id, line.number) {
+            let message = format!("game {} is already defined on line {line_number}", game.id);
+            return Err(line.error(identifier, &message));
+        }
+        games.push(game);
+    }
+    return Ok(games);
+}
+
+/// Parses a `Game <id>: <draw>; <draw>; ...` line, also returning the identifier token to locate errors.
+fn get_game<'a>(line: &InputLine<'a>) -> Result<(Game, &'a str)> {
+    let Some(game) = line.text.strip_prefix("Game ") else {
+        return Err(line.error(line.text, "expected `Game <id>: <draws>`"));
+    };
+    let Some((identifier, draws)) = game.split_once(':') else {
+        return Err(line.error(game, "expected `:` after the game identifier"));
+    };
+
+    let identifier = identifier.trim();
+    let id = line.parse::<i32>(identifier, "a game identifier")?;
+    if id < 0 {
+        return Err(line.error(identifier, "a game identifier cannot be negative"));
+    }
+
+    let mut configurations_per_game: Vec<Configuration> = Vec::new();
+    for draw in draws.split(';') {
+        configurations_per_game.push(get_configuration(line, draw.trim())?);
+    }
+
+    let game = Game {
+        id,
+        draws: configurations_per_game,
     };
-    let valid_identifiers = part_1(input.to_owned(), current_configuration);
-    let cube_powers = part_2(input.to_owned());
+    return Ok((game, identifier));
+}
+
+/// Parses a `<number> <color>, <number> <color>, ...` draw.
+fn get_configuration(line: &InputLine, draw: &str) -> Result<Configuration> {
+    let mut configuration = Configuration::default();
+
+    for cubes in draw.split(',') {
+        let cubes = cubes.trim();
+        let Some((number, color)) = cubes.split_once(' ') else {
+            return Err(line.error(cubes, "expected `<number> <color>`"));
+        };
 
-    let total_identifier_sum: i32 = valid_identifiers.iter().sum();
-    let total_cube_power_sum: i32 = cube_powers.iter().sum();
+        let number = line.parse::<i32>(number, "a number of cubes")?;
+        if number < 0 {
+            return Err(line.error(cubes, "a number of cubes cannot be negative"));
+        }
+        if color.is_empty() || !color.chars().all(|x| x.is_ascii_alphabetic()) {
+            return Err(line.error(color, "expected a color"));
+        }
+        if configuration.get(color).checked_add(number).is_none() {
+            return Err(line.error(cubes, "too many cubes"));
+        }
+        configuration.add(color, number);
+    }
 
-    println!("Part 1 Result: {total_identifier_sum}");
-    println!("Part 2 Result: {total_cube_power_sum}");
+    return Ok(configuration);
 }
 
-fn part_1(input: String, current_configuration: Configuration) -> HashSet<i32> {
-    let id_regex = Regex::new(r"Game (\d+):(.*)").unwrap();
+/// A color of a draw with more cubes than the bag holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Excess {
+    /// Index of the draw in the game, starting at 1.
+    pub draw: usize,
+    pub color: String,
+    pub drawn: i32,
+    pub available: i32,
+}
 
-    let mut invalid_game_identifiers: HashSet<i32> = HashSet::new();
-    let mut game_identifiers: HashSet<i32> = HashSet::new();
-    for line in input.lines() {
-        // println!("{line}");
-        let (_, [game_identifier, game]) = id_regex.captures(line).unwrap().extract();
+impl Excess {
+    /// How many more cubes were drawn than the bag holds.
+    pub fn excess(&self) -> i32 {
+        return self.drawn - self.available;
+    }
 
-        let sets: Vec<&str> = game.split(';').map(|x| x.trim()).collect();
-        game_identifiers.insert(game_identifier.parse::<i32>().unwrap());
+    pub fn to_json(&self) -> JsonObject {
+        return JsonObject::new()
+            .number("draw", self.draw)
+            .string("color", &self.color)
+            .number("drawn", self.drawn)
+            .number("available", self.available)
+            .number("excess", self.excess());
+    }
+}
 
-        let configurations_per_game = get_configurations_per_game(sets);
-        // println!("{:?}", configurations_per_game);
+/// Whether a game is possible with the bag, and if it is not, why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameReport {
+    pub id: i32,
+    /// Every color of every draw exceeding the bag, in order, empty if the game is possible.
+    pub excesses: Vec<Excess>,
+}
+
+impl GameReport {
+    pub fn is_valid(&self) -> bool {
+        return self.excesses.is_empty();
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let excesses: Vec<String> = self
+            .excesses
+            .iter()
+            .map(|excess| excess.to_json().to_string())
+            .collect();
+        return JsonObject::new()
+            .number("id", self.id)
+            .raw("valid", self.is_valid().to_string())
+            .raw("excesses", format!("[{}]", excesses.join(",")));
+    }
+}
 
-        for possible_configuration in configurations_per_game.iter() {
-            if !is_possible(current_configuration, *possible_configuration) {
-                invalid_game_identifiers.insert(game_identifier.parse::<i32>().unwrap());
+impl fmt::Display for GameReport {
+    /// Describes the excesses, e.g. `draw 1: red +8, draw 3: blue +1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, excess) in self.excesses.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
             }
+            write!(
+                f,
+                "draw {}: {} +{}",
+                excess.draw,
+                excess.color,
+                excess.excess()
+            )?;
         }
+        return Ok(());
+    }
+}
+
+/// Checks every game against the bag, in the order of the input.
+pub fn get_game_reports(games: &[Game], current_configuration: &Configuration) -> Vec<GameReport> {
+    let mut game_reports: Vec<GameReport> = Vec::new();
+    for game in games {
+        let mut excesses: Vec<Excess> = Vec::new();
+
+        for (index, possible_configuration) in game.draws.iter().enumerate() {
+            for (color, number) in possible_configuration.colors() {
+                let available = current_configuration.get(color);
+                if number > available {
+                    excesses.push(Excess {
+                        draw: index + 1,
+                        color: color.to_string(),
+                        drawn: number,
+                        available,
+                    });
+                }
+            }
+        }
+        game_reports.push(GameReport {
+            id: game.id,
+            excesses,
+        });
+    }
+    return game_reports;
+}
 
-        // println!("{:?}", invalid_game_identifiers);
+/// Table of the reports, one line per game with its status and the excesses of invalid games.
+pub fn get_game_reports_table(game_reports: &[GameReport]) -> String {
+    let mut table = format!("{:>4} | {:<7} | Exceeded\n", "Game", "Status");
+    table += &format!("{:-<4}-+-{:-<7}-+-{:-<8}\n", "", "", "");
+    for game_report in game_reports {
+        let status = if game_report.is_valid() {
+            "valid"
+        } else {
+            "invalid"
+        };
+        let row = format!("{:>4} | {:<7} | {game_report}", game_report.id, status);
+        table += row.trim_end();
+        table += "\n";
     }
-    let valid_game_identifiers: HashSet<_> = game_identifiers
-        .difference(&invalid_game_identifiers)
-        .map(|x| *x)
+    return table;
+}
+
+fn part_1(games: &[Game], current_configuration: &Configuration) -> Vec<GameReport> {
+    return get_game_reports(games, current_configuration);
+}
+
+/// Power of the smallest bag of each game, `None` for the games whose power overflows.
+fn part_2(games: &[Game]) -> Vec<Option<i64>> {
+    // A color missing from a game still counts, with no cube, so the power is over every color.
+    let colors = get_colors(games);
+    let mut minimal_cube_powers: Vec<Option<i64>> = Vec::new();
+    for game in games {
+        let minimal_configuration = get_minimal_working_configuration(&game.draws);
+        minimal_cube_powers.push(minimal_configuration.power(colors.iter().map(|x| x.as_str())))
+    }
+    return minimal_cube_powers;
+}
+
+/// Every color of cube seen in the games.
+pub fn get_colors(games: &[Game]) -> BTreeSet<String> {
+    return games
+        .iter()
+        .flat_map(|game| game.draws.iter())
+        .flat_map(|configuration| configuration.colors())
+        .map(|(color, _)| color.to_string())
         .collect();
-    return valid_game_identifiers;
 }
 
-fn part_2(input: String) -> Vec<i32> {
-    let id_regex = Regex::new(r"Game (\d+):(.*)").unwrap();
+pub fn get_minimal_working_configuration(
+    configurations_per_game: &[Configuration],
+) -> Configuration {
+    let mut minimal_configuration = Configuration::default();
+    for possible_configuration in configurations_per_game.iter() {
+        for (color, number) in possible_configuration.colors() {
+            let max_number = max(minimal_configuration.get(color), number);
+            minimal_configuration
+                .cubes
+                .insert(color.to_string(), max_number);
+        }
+    }
 
-    let mut minimal_cube_powers: Vec<i32> = Vec::new();
-    for line in input.lines() {
-        // println!("{line}");
-        let (_, [__, game]) = id_regex.captures(line).unwrap().extract();
+    return minimal_configuration;
+}
 
-        let sets: Vec<&str> = game.split(';').map(|x| x.trim()).collect();
+/// A number of cubes of each color, e.g. the content of a bag or a set revealed during a game.
+///
+/// Colors are whatever the input names them, a color that is not listed has no cube.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Configuration {
+    cubes: BTreeMap<String, i32>,
+}
 
-        let configurations_per_game = get_configurations_per_game(sets);
+impl Configuration {
+    pub fn new<'a>(cubes: impl IntoIterator<Item = (&'a str, i32)>) -> Configuration {
+        let mut configuration = Configuration::default();
+        for (color, number) in cubes {
+            configuration.add(color, number);
+        }
+        return configuration;
+    }
 
-        let minimal_configuration = get_minimal_working_configuration(configurations_per_game);
-        let cube_power = minimal_configuration.nb_blues
-            * minimal_configuration.nb_reds
-            * minimal_configuration.nb_greens;
-        minimal_cube_powers.push(cube_power)
+    /// Number of cubes of that color, 0 if the color is not listed.
+    pub fn get(&self, color: &str) -> i32 {
+        return self.cubes.get(color).copied().unwrap_or(0);
+    }
+
+    pub fn add(&mut self, color: &str, number: i32) {
+        *self.cubes.entry(color.to_string()).or_insert(0) += number;
+    }
+
+    /// Listed colors and their number of cubes, in alphabetical order.
+    pub fn colors(&self) -> impl Iterator<Item = (&str, i32)> {
+        return self
+            .cubes
+            .iter()
+            .map(|(color, number)| (color.as_str(), *number));
+    }
+
+    /// Product of the numbers of cubes of the given colors, `None` if it overflows 64-bit numbers.
+    pub fn power<'a>(&self, colors: impl IntoIterator<Item = &'a str>) -> Option<i64> {
+        return colors.into_iter().try_fold(1i64, |power, color| {
+            power.checked_mul(self.get(color) as i64)
+        });
     }
-    return minimal_cube_powers;
 }
 
-fn get_configurations_per_game(sets: Vec<&str>) -> Vec<Configuration> {
-    let configuration_regex = Regex::new(r"(\d+ \w+)+").unwrap();
-    let mut configurations_per_game: Vec<Configuration> = Vec::new();
+/// Whether the cubes of `potential_configuration` could all have been drawn from `current_configuration`.
+pub fn is_possible(
+    current_configuration: &Configuration,
+    potential_configuration: &Configuration,
+) -> bool {
+    return potential_configuration
+        .colors()
+        .all(|(color, number)| current_configuration.get(color) >= number);
+}
 
-    for set in sets.iter() {
-        let matches: Vec<_> = configuration_regex
-            .find_iter(set)
-            .map(|configurations| configurations.as_str())
-            .collect();
+impl fmt::Display for Configuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (color, number)) in self.colors().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{number} {color}")?;
+        }
+        return Ok(());
+    }
+}
 
-        let configuration: Configuration = get_configuration(matches);
-        configurations_per_game.push(configuration);
+impl FromStr for Configuration {
+    type Err = Error;
+
+    /// Parses cubes written like a draw of the input, e.g. `12 red, 13 green, 14 blue`.
+    fn from_str(text: &str) -> Result<Self> {
+        let line = InputLine {
+            day: 2,
+            number: 1,
+            text,
+        };
+        return get_configuration(&line, text.trim());
     }
+}
 
-    return configurations_per_game;
+/// Limits on the cubes of the bag the games are played with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bag {
+    /// Exactly these cubes: there is no cube of a color that is not listed.
+    Exact(Configuration),
+    /// At most these cubes of the listed colors, and any number of cubes of the other colors.
+    AtMost(Configuration),
 }
 
-fn get_minimal_working_configuration(configurations_per_game: Vec<Configuration>) -> Configuration {
-    let mut max_reds = 0;
-    let mut max_greens = 0;
-    let mut max_blues = 0;
-    for possible_configuration in configurations_per_game.iter() {
-        max_reds = max(max_reds, possible_configuration.nb_reds);
-        max_greens = max(max_greens, possible_configuration.nb_greens);
-        max_blues = max(max_blues, possible_configuration.nb_blues);
+/// Whether every draw of the game could have been made from the bag.
+pub fn is_game_possible(bag: &Bag, game: &Game) -> bool {
+    return game.draws.iter().all(|draw| match bag {
+        Bag::Exact(configuration) => is_possible(configuration, draw),
+        Bag::AtMost(configuration) => draw
+            .colors()
+            .filter(|(color, _)| configuration.cubes.contains_key(*color))
+            .all(|(color, number)| configuration.get(color) >= number),
+    });
+}
+
+/// The games that are possible with the bag, in the order of the input.
+pub fn get_possible_games<'a>(games: &'a [Game], bag: &Bag) -> Vec<&'a Game> {
+    return games
+        .iter()
+        .filter(|game| is_game_possible(bag, game))
+        .collect();
+}
+
+/// The bag with the fewest cubes in total that makes at least `fraction` of the games possible.
+///
+/// A game is possible exactly when the bag holds its minimal working configuration, so the
+/// number of cubes of each color in the best bag is the one of some game. Those candidates are
+/// searched color by color, from the smallest, giving up on a branch as soon as it holds more
+/// cubes than the best bag found so far or leaves too few games possible.
+///
+/// The fraction must be between 0 and 1, otherwise no bag can make enough games possible.
+pub fn get_smallest_bag(games: &[Game], fraction: f64) -> Result<Configuration> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(Error::Puzzle {
+            day: 2,
+            message: format!("expected a fraction of the games between 0 and 1, found {fraction}"),
+        });
     }
 
-    return Configuration {
-        nb_reds: max_reds,
-        nb_greens: max_greens,
-        nb_blues: max_blues,
+    let colors: Vec<String> = get_colors(games).into_iter().collect();
+    let minimal_configurations: Vec<Configuration> = games
+        .iter()
+        .map(|game| get_minimal_working_configuration(&game.draws))
+        .collect();
+    // The small tolerance keeps e.g. 0.9 of 10 games from rounding up to 10 games.
+    let required_games = (fraction * games.len() as f64 - 1e-9).ceil().max(0.0) as usize;
+
+    let mut search = BagSearch {
+        colors: &colors,
+        minimal_configurations: &minimal_configurations,
+        required_games,
+        bag: Vec::new(),
+        best_bag: None,
+    };
+    let all_games: Vec<usize> = (0..games.len()).collect();
+    search.search(&all_games, 0);
+
+    let Some((best_bag, _)) = search.best_bag else {
+        return Err(Error::Puzzle {
+            day: 2,
+            message: format!("no bag makes {fraction} of the games possible"),
+        });
     };
+    return Ok(Configuration::new(
+        colors.iter().map(|x| x.as_str()).zip(best_bag),
+    ));
+}
+
+/// State of the branch and bound search of `get_smallest_bag`.
+struct BagSearch<'a> {
+    colors: &'a [String],
+    minimal_configurations: &'a [Configuration],
+    required_games: usize,
+    /// Number of cubes of the colors chosen so far.
+    bag: Vec<i32>,
+    /// Best complete bag so far, and its total number of cubes.
+    best_bag: Option<(Vec<i32>, i64)>,
+}
+
+impl BagSearch<'_> {
+    /// Chooses the number of cubes of the next color, knowing which games are still possible.
+    fn search(&mut self, possible_games: &[usize], total_cubes: i64) {
+        if self.bag.len() == self.colors.len() {
+            if self
+                .best_bag
+                .as_ref()
+                .is_none_or(|(_, best)| total_cubes < *best)
+            {
+                self.best_bag = Some((self.bag.clone(), total_cubes));
+            }
+            return;
+        }
+
+        let color = &self.colors[self.bag.len()];
+        let mut candidates: Vec<i32> = possible_games
+            .iter()
+            .map(|game| self.minimal_configurations[*game].get(color))
+            .collect();
+        candidates.push(0);
+        candidates.sort();
+        candidates.dedup();
+
+        for number in candidates {
+            let total_cubes = total_cubes + number as i64;
+            if self
+                .best_bag
+                .as_ref()
+                .is_some_and(|(_, best)| total_cubes >= *best)
+            {
+                // Candidates are sorted, the next ones can only hold more cubes.
+                return;
+            }
+
+            let still_possible_games: Vec<usize> = possible_games
+                .iter()
+                .copied()
+                .filter(|game| self.minimal_configurations[*game].get(color) <= number)
+                .collect();
+            if still_possible_games.len() < self.required_games {
+                continue;
+            }
+
+            self.bag.push(number);
+            self.search(&still_possible_games, total_cubes);
+            self.bag.pop();
+        }
+    }
+}
+
+impl fmt::Display for Game {
+    /// Writes the game like a line of the input.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        for (index, draw) in self.draws.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{draw}")?;
+        }
+        return Ok(());
+    }
+}
+
+/// How the number of cubes of a color in a draw is picked by the generator.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CountDistribution {
+    /// Any number between the two bounds, included, with the same probability.
+    Uniform(i32, i32),
+    /// Mostly small numbers, with the given mean, like counting failures before a success.
+    Geometric(f64),
 }
-#[derive(Debug, Copy, Clone)]
-struct Configuration {
-    nb_reds: i32,
-    nb_blues: i32,
-    nb_greens: i32,
+
+impl CountDistribution {
+    /// Whether a draw can have any cube of a color with that distribution.
+    fn can_have_cubes(&self) -> bool {
+        return match *self {
+            CountDistribution::Uniform(_, max) => max > 0,
+            CountDistribution::Geometric(mean) => mean > 0.0,
+        };
+    }
+
+    /// Random number of cubes, at least `at_least`, which the distribution must allow.
+    fn get_random_count(&self, random: &mut Random, at_least: i32) -> i32 {
+        return match *self {
+            CountDistribution::Uniform(min, max) => {
+                random.next_between(min.max(at_least) as u64, max as u64) as i32
+            }
+            CountDistribution::Geometric(mean) => {
+                // Inverse transform sampling, with the success probability giving that mean. A
+                // success probability rounding to 0 or 1 gives an infinite or null logarithm,
+                // hence the clamping.
+                let success = 1.0 / (1.0 + mean);
+                let uniform = 1.0 - random.next_fraction();
+                let failures = (uniform.ln() / (-success).ln_1p())
+                    .floor()
+                    .clamp(0.0, i32::MAX as f64) as i32;
+                // The distribution is memoryless: past `at_least`, it is the same one shifted.
+                failures.saturating_add(at_least)
+            }
+        };
+    }
+}
+
+/// A color of cube and the distribution of its counts, written `red=0..20` for a uniform
+/// distribution, or `red~4.5` for a geometric one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorDistribution {
+    pub color: String,
+    pub distribution: CountDistribution,
 }
 
-fn get_configuration(set_inputs: Vec<&str>) -> Configuration {
-    let nb_color_regex = Regex::new(r"(\d+) (\w+)").unwrap();
+impl FromStr for ColorDistribution {
+    type Err = String;
 
-    let mut nb_reds = 0;
-    let mut nb_greens = 0;
-    let mut nb_blues = 0;
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        let expected =
+            format!("expected `<color>=<min>..<max>` or `<color>~<mean>`, found {text:?}");
+
+        let (color, distribution) = if let Some((color, bounds)) = text.split_once('=') {
+            let (min, max) = bounds.split_once("..").ok_or(&expected)?;
+            let min = min.trim().parse::<i32>().map_err(|_| &expected)?;
+            let max = max.trim().parse::<i32>().map_err(|_| &expected)?;
+            if min < 0 || min > max {
+                return Err(format!("expected 0 <= min <= max, found {text:?}"));
+            }
+            (color, CountDistribution::Uniform(min, max))
+        } else if let Some((color, mean)) = text.split_once('~') {
+            let mean = mean.trim().parse::<f64>().map_err(|_| &expected)?;
+            if !(mean >= 0.0 && mean.is_finite()) {
+                return Err(format!("expected a positive mean, found {text:?}"));
+            }
+            (color, CountDistribution::Geometric(mean))
+        } else {
+            return Err(expected);
+        };
+
+        let color = color.trim();
+        if color.is_empty() || !color.chars().all(|x| x.is_ascii_alphabetic()) {
+            return Err(format!("expected a color, found {color:?}"));
+        }
+        return Ok(ColorDistribution {
+            color: color.to_string(),
+            distribution,
+        });
+    }
+}
 
-    for input in set_inputs {
-        let (_, [number, color]) = nb_color_regex.captures(input).unwrap().extract();
+/// Settings of the generator of random games.
+#[derive(Debug, Clone)]
+pub struct GameGenerator {
+    pub number_of_games: usize,
+    /// Smallest and largest number of draws of a game, included.
+    pub draws_per_game: (usize, usize),
+    pub colors: Vec<ColorDistribution>,
+    /// The same seed always generates the same games.
+    pub seed: u64,
+}
+
+impl GameGenerator {
+    /// Random games numbered from 1, which are valid inputs: every draw has at least one cube.
+    ///
+    /// At least one color must be able to have cubes, otherwise no draw could be written.
+    pub fn generate(&self) -> Result<Vec<Game>> {
+        if !self
+            .colors
+            .iter()
+            .any(|color_distribution| color_distribution.distribution.can_have_cubes())
+        {
+            return Err(Error::Puzzle {
+                day: 2,
+                message: "every draw needs a cube, but no color can have any".to_string(),
+            });
+        }
+        let mut colors: BTreeSet<&str> = BTreeSet::new();
+        for color_distribution in self.colors.iter() {
+            if !colors.insert(&color_distribution.color) {
+                return Err(Error::Puzzle {
+                    day: 2,
+                    message: format!("{} cubes are listed twice", color_distribution.color),
+                });
+            }
+        }
+
+        let mut random = Random::new(self.seed);
+        let mut games: Vec<Game> = Vec::new();
+
+        for id in 1..=self.number_of_games {
+            let (min_draws, max_draws) = self.draws_per_game;
+            let number_of_draws = random.next_between(min_draws as u64, max_draws as u64);
+
+            let mut draws: Vec<Configuration> = Vec::new();
+            for _ in 0..number_of_draws.max(1) {
+                draws.push(self.get_random_draw(&mut random));
+            }
+            games.push(Game {
+                id: id as i32,
+                draws,
+            });
+        }
+        return Ok(games);
+    }
+
+    /// A draw with at least one cube: when no color got any, one of the colors that can have
+    /// cubes gets at least one, so that each number stays within the distribution of its color.
+    fn get_random_draw(&self, random: &mut Random) -> Configuration {
+        let mut draw = Configuration::default();
+        for color_distribution in self.colors.iter() {
+            let number = color_distribution.distribution.get_random_count(random, 0);
+            if number > 0 {
+                draw.add(&color_distribution.color, number);
+            }
+        }
 
-        match color {
-            "red" => nb_reds += number.parse::<i32>().unwrap(),
-            "green" => nb_greens += number.parse::<i32>().unwrap(),
-            "blue" => nb_blues += number.parse::<i32>().unwrap(),
-            _ => eprintln!("Problem parsing arguments."),
+        if draw.cubes.is_empty() {
+            let candidates: Vec<&ColorDistribution> = self
+                .colors
+                .iter()
+                .filter(|color_distribution| color_distribution.distribution.can_have_cubes())
+                .collect();
+            let index = random.next_between(0, candidates.len() as u64 - 1) as usize;
+            let number = candidates[index].distribution.get_random_count(random, 1);
+            draw.add(&candidates[index].color, number);
         }
+        return draw;
     }
+}
+
+/// Spread of a set of numbers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub min: i64,
+    pub mean: f64,
+    pub median: i64,
+    pub percentile_90: i64,
+    pub max: i64,
+}
+
+impl Statistics {
+    /// Summarises the values, there must be at least one.
+    ///
+    /// Percentiles are nearest-rank: the smallest value with at least that share of the values
+    /// below or equal to it.
+    pub fn from_values(mut values: Vec<i64>) -> Statistics {
+        values.sort();
+        let percentile = |share: f64| -> i64 {
+            let rank = (share * values.len() as f64).ceil().max(1.0) as usize;
+            return values[rank - 1];
+        };
+        let sum: i128 = values.iter().map(|x| *x as i128).sum();
 
-    let configuration = Configuration {
-        nb_reds: nb_reds,
-        nb_blues: nb_blues,
-        nb_greens: nb_greens,
+        return Statistics {
+            min: values[0],
+            mean: sum as f64 / values.len() as f64,
+            median: percentile(0.5),
+            percentile_90: percentile(0.9),
+            max: values[values.len() - 1],
+        };
+    }
+}
+
+/// Statistics about the cubes of one color, or of all colors together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorSummary {
+    /// Color of the cubes, `None` for the cubes of all colors.
+    pub color: Option<String>,
+    /// Number of cubes of every draw, a draw without that color counting as 0.
+    pub draws: Statistics,
+    /// Number of cubes of the minimal working configuration of every game.
+    pub minimal_bags: Statistics,
+}
+
+/// Statistics of every color of the games, followed by the ones of all colors together.
+///
+/// There must be at least one game.
+pub fn get_summary(games: &[Game]) -> Vec<ColorSummary> {
+    let minimal_configurations: Vec<Configuration> = games
+        .iter()
+        .map(|game| get_minimal_working_configuration(&game.draws))
+        .collect();
+    let draws: Vec<&Configuration> = games.iter().flat_map(|game| game.draws.iter()).collect();
+    // Colors can each have up to `i32::MAX` cubes, so their total only fits in 64 bits.
+    let total = |configuration: &Configuration| -> i64 {
+        return configuration
+            .colors()
+            .map(|(_, number)| number as i64)
+            .sum();
     };
 
-    return configuration;
-    // println!("{:? :?}", number, color);
+    let mut summaries: Vec<ColorSummary> = Vec::new();
+    for color in get_colors(games) {
+        summaries.push(ColorSummary {
+            draws: Statistics::from_values(
+                draws.iter().map(|draw| draw.get(&color) as i64).collect(),
+            ),
+            minimal_bags: Statistics::from_values(
+                minimal_configurations
+                    .iter()
+                    .map(|configuration| configuration.get(&color) as i64)
+                    .collect(),
+            ),
+            color: Some(color),
+        });
+    }
+    summaries.push(ColorSummary {
+        color: None,
+        draws: Statistics::from_values(draws.iter().map(|draw| total(draw)).collect()),
+        minimal_bags: Statistics::from_values(minimal_configurations.iter().map(total).collect()),
+    });
+    return summaries;
 }
 
-fn is_possible(
-    current_configuration: Configuration,
-    potential_configuration: Configuration,
-) -> bool {
-    return current_configuration.nb_blues >= potential_configuration.nb_blues
-        && current_configuration.nb_greens >= potential_configuration.nb_greens
-        && current_configuration.nb_reds >= potential_configuration.nb_reds;
+/// Table of the summaries, one line for the draws and one for the minimal bags of each color.
+pub fn get_summary_table(summaries: &[ColorSummary]) -> String {
+    let mut table = format!(
+        "{:<12} | {:<11} | {:>5} | {:>7} | {:>6} | {:>5} | {:>5}\n",
+        "Color", "Cubes of", "Min", "Mean", "Median", "P90", "Max"
+    );
+    table += &format!(
+        "{:-<12}-+-{:-<11}-+-{:-<5}-+-{:-<7}-+-{:-<6}-+-{:-<5}-+-{:-<5}\n",
+        "", "", "", "", "", "", ""
+    );
+    for color_summary in summaries {
+        let color = color_summary.color.as_deref().unwrap_or("(all)");
+        for (cubes_of, statistics) in [
+            ("draws", &color_summary.draws),
+            ("minimal bag", &color_summary.minimal_bags),
+        ] {
+            table += &format!(
+                "{:<12} | {:<11} | {:>5} | {:>7.2} | {:>6} | {:>5} | {:>5}\n",
+                color,
+                cubes_of,
+                statistics.min,
+                statistics.mean,
+                statistics.median,
+                statistics.percentile_90,
+                statistics.max
+            );
+        }
+    }
+    return table;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn solves_part_1_example() {
+        let games = CubeGames::parse(EXAMPLE).unwrap();
+        assert_eq!(games.part_1().unwrap(), Answer::from(8));
+    }
+
+    #[test]
+    fn solves_part_2_example() {
+        let games = CubeGames::parse(EXAMPLE).unwrap();
+        assert_eq!(games.part_2().unwrap(), Answer::from(2286));
+    }
+
+    #[test]
+    fn finds_possible_games() {
+        let games = CubeGames::parse(EXAMPLE).unwrap();
+        let game_reports = part_1(&games.games, &get_current_configuration());
+
+        let valid_identifiers: Vec<i32> = game_reports
+            .iter()
+            .filter(|report| report.is_valid())
+            .map(|report| report.id)
+            .collect();
+        assert_eq!(valid_identifiers, vec![1, 2, 5]);
+        assert_eq!(game_reports[2].to_string(), "draw 1: red +8");
+        assert_eq!(
+            game_reports[3].to_string(),
+            "draw 3: blue +1, draw 3: red +2"
+        );
+        assert_eq!(
+            game_reports[2].to_json().to_string(),
+            r#"{"id":3,"valid":false,"excesses":[{"draw":1,"color":"red","drawn":20,"available":12,"excess":8}]}"#
+        );
+        assert_eq!(
+            game_reports[0].to_json().to_string(),
+            r#"{"id":1,"valid":true,"excesses":[]}"#
+        );
+    }
+
+    #[test]
+    fn writes_reports_as_a_table() {
+        let games = get_games(EXAMPLE).unwrap();
+        let game_reports = get_game_reports(&games, &get_current_configuration());
+
+        assert_eq!(
+            get_game_reports_table(&game_reports),
+            "Game | Status  | Exceeded
+-----+---------+---------
+   1 | valid   |
+   2 | valid   |
+   3 | invalid | draw 1: red +8
+   4 | invalid | draw 3: blue +1, draw 3: red +2
+   5 | valid   |
+"
+        );
+    }
+
+    #[test]
+    fn finds_minimal_working_configuration() {
+        let games = CubeGames::parse(EXAMPLE).unwrap();
+        let minimal_configuration = get_minimal_working_configuration(&games.games[0].draws);
+
+        assert_eq!(minimal_configuration.get("red"), 4);
+        assert_eq!(minimal_configuration.get("green"), 2);
+        assert_eq!(minimal_configuration.get("blue"), 6);
+    }
+
+    #[test]
+    fn accepts_any_color() {
+        let input = "Game 1: 2 yellow, 1 red; 3 yellow
+Game 2: 1 purple, 2 red, 1 yellow
+";
+        let games = CubeGames::parse(input).unwrap();
+
+        let bag = Configuration::new([("red", 2), ("yellow", 3)]);
+        let game_reports = part_1(&games.games, &bag);
+        assert!(game_reports[0].is_valid());
+        assert_eq!(game_reports[1].to_string(), "draw 1: purple +1");
+        // Game 1 has no purple cube, so its power is 0 once purple is part of the colors.
+        assert_eq!(part_2(&games.games), vec![Some(0), Some(2)]);
+        assert_eq!(games.part_2().unwrap(), Answer::from(2));
+    }
+
+    #[test]
+    fn reports_answers_that_overflow() {
+        let games = CubeGames::parse("Game 1: 1 red\nGame 2147483647: 1 red\n").unwrap();
+        assert_eq!(
+            games.part_1().err().unwrap().to_string(),
+            "Day 2: the sum of the identifiers overflows at game 2147483647"
+        );
+
+        let games = CubeGames::parse("Game 1: 2000000000 red, 2000000000 blue, 2000000000 green\n")
+            .unwrap();
+        assert_eq!(
+            games.part_2().err().unwrap().to_string(),
+            "Day 2: the power of game 1 overflows 64-bit numbers"
+        );
+
+        let game = "2000000000 red, 2000000000 blue, 1 green";
+        let games =
+            CubeGames::parse(&format!("Game 1: {game}\nGame 2: {game}\nGame 3: {game}\n")).unwrap();
+        assert_eq!(
+            games.part_2().err().unwrap().to_string(),
+            "Day 2: the sum of the powers overflows at game 3"
+        );
+    }
+
+    #[test]
+    fn parses_games() {
+        let games = get_games("Game 7: 3 blue, 4 red; 2 green\n").unwrap();
+        assert_eq!(
+            games,
+            vec![Game {
+                id: 7,
+                draws: vec![
+                    Configuration::new([("blue", 3), ("red", 4)]),
+                    Configuration::new([("green", 2)]),
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn locates_malformed_games() {
+        let cases = [
+            ("Gme 1: 3 blue", 1, 1, "expected `Game <id>: <draws>`"),
+            (
+                "Game 1 3 blue",
+                1,
+                6,
+                "expected `:` after the game identifier",
+            ),
+            ("Game one: 3 blue", 1, 6, "expected a game identifier"),
+            (
+                "Game 1: 3 blue; -2 red",
+                1,
+                17,
+                "a number of cubes cannot be negative",
+            ),
+            (
+                "Game 1: 3 blue, many red",
+                1,
+                17,
+                "expected a number of cubes",
+            ),
+            ("Game 1: 3 blue, 4", 1, 17, "expected `<number> <color>`"),
+            ("Game 1: 3 blue, 4 dark-red", 1, 19, "expected a color"),
+            ("Game 1: 2147483647 red, 1 red", 1, 25, "too many cubes"),
+            (
+                "Game 1: 3 blue\nGame 1: 2 red",
+                2,
+                6,
+                "game 1 is already defined on line 1",
+            ),
+        ];
+
+        for (input, expected_line, expected_column, expected_message) in cases {
+            let Err(Error::Parse {
+                line,
+                column,
+                message,
+                ..
+            }) = get_games(input)
+            else {
+                panic!("{input:?} should not parse");
+            };
+            assert_eq!(
+                (line, column, message.as_str()),
+                (expected_line, expected_column, expected_message),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn finds_possible_games_with_any_bag() {
+        let games = get_games(EXAMPLE).unwrap();
+        let identifiers = |bag: &Bag| -> Vec<i32> {
+            return get_possible_games(&games, bag)
+                .iter()
+                .map(|game| game.id)
+                .collect();
+        };
+
+        let bag = Bag::Exact(get_current_configuration());
+        assert_eq!(identifiers(&bag), vec![1, 2, 5]);
+        let bag = Bag::AtMost("10 blue".parse().unwrap());
+        assert_eq!(identifiers(&bag), vec![1, 2, 3, 5]);
+        let bag = Bag::Exact("10 blue".parse().unwrap());
+        assert_eq!(identifiers(&bag), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn finds_smallest_bag() {
+        let games = get_games(EXAMPLE).unwrap();
+
+        let bag = get_smallest_bag(&games, 1.0).unwrap();
+        assert_eq!(bag.to_string(), "15 blue, 13 green, 20 red");
+
+        // Leaving out game 3 saves the most cubes, then game 4.
+        let bag = get_smallest_bag(&games, 0.8).unwrap();
+        assert_eq!(bag.to_string(), "15 blue, 3 green, 14 red");
+        let bag = get_smallest_bag(&games, 0.6).unwrap();
+        assert_eq!(bag.to_string(), "6 blue, 3 green, 6 red");
+        assert_eq!(get_possible_games(&games, &Bag::Exact(bag)).len(), 3);
+
+        let bag = get_smallest_bag(&games, 0.0).unwrap();
+        assert_eq!(bag.to_string(), "0 blue, 0 green, 0 red");
+    }
+
+    #[test]
+    fn rejects_fractions_outside_of_0_and_1() {
+        let games = get_games(EXAMPLE).unwrap();
+
+        for fraction in [1.5, -0.1, f64::NAN] {
+            assert!(matches!(
+                get_smallest_bag(&games, fraction),
+                Err(Error::Puzzle { day: 2, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn reads_and_writes_configurations() {
+        let configuration: Configuration = "12 red, 13 green, 14 blue".parse().unwrap();
+        assert_eq!(configuration.to_string(), "14 blue, 13 green, 12 red");
+        assert!("12 red, lots blue".parse::<Configuration>().is_err());
+    }
+
+    fn get_generator(seed: u64) -> GameGenerator {
+        return GameGenerator {
+            number_of_games: 50,
+            draws_per_game: (1, 6),
+            colors: vec![
+                "red=0..20".parse().unwrap(),
+                "green=0..3".parse().unwrap(),
+                "yellow~2.5".parse().unwrap(),
+            ],
+            seed,
+        };
+    }
+
+    #[test]
+    fn generates_valid_games() {
+        let games = get_generator(7).generate().unwrap();
+        let input: String = games.iter().map(|game| format!("{game}\n")).collect();
+
+        assert_eq!(games.len(), 50);
+        assert_eq!(get_games(&input).unwrap(), games);
+        assert!(games.iter().all(|game| (1..=6).contains(&game.draws.len())));
+        assert!(games.iter().flat_map(|game| game.draws.iter()).all(|draw| {
+            return draw.get("red") <= 20 && draw.get("green") <= 3;
+        }));
+    }
+
+    #[test]
+    fn keeps_the_counts_within_their_bounds() {
+        let mut generator = get_generator(7);
+        generator.colors = vec!["red=0..0".parse().unwrap(), "blue=0..2".parse().unwrap()];
+        let games = generator.generate().unwrap();
+
+        let draws: Vec<&Configuration> = games.iter().flat_map(|game| game.draws.iter()).collect();
+        assert!(draws.iter().all(|draw| draw.get("red") == 0));
+        assert!(draws.iter().all(|draw| (1..=2).contains(&draw.get("blue"))));
+        // A draw without cubes gets at least one blue cube within its bounds, so both counts show up.
+        assert!(draws.iter().any(|draw| draw.get("blue") == 2));
+
+        generator.colors = vec!["red=0..0".parse().unwrap(), "blue~0".parse().unwrap()];
+        assert!(matches!(
+            generator.generate(),
+            Err(Error::Puzzle { day: 2, .. })
+        ));
+        generator.colors = Vec::new();
+        assert!(generator.generate().is_err());
+    }
+
+    #[test]
+    fn generates_games_with_extreme_means() {
+        let mut generator = get_generator(7);
+        generator.colors = vec!["red~1e-300".parse().unwrap()];
+        let games = generator.generate().unwrap();
+        let draws: Vec<&Configuration> = games.iter().flat_map(|game| game.draws.iter()).collect();
+        assert!(draws.iter().all(|draw| draw.get("red") == 1));
+
+        generator.colors = vec!["red~1e300".parse().unwrap()];
+        let games = generator.generate().unwrap();
+        let draws: Vec<&Configuration> = games.iter().flat_map(|game| game.draws.iter()).collect();
+        assert!(draws.iter().all(|draw| draw.get("red") == i32::MAX));
+    }
+
+    #[test]
+    fn rejects_colors_listed_twice() {
+        let mut generator = get_generator(7);
+        generator.colors = vec![
+            "red=2147483647..2147483647".parse().unwrap(),
+            "red~1".parse().unwrap(),
+        ];
+        assert_eq!(
+            generator.generate().err().unwrap().to_string(),
+            "Day 2: red cubes are listed twice"
+        );
+    }
+
+    #[test]
+    fn generates_the_same_games_from_the_same_seed() {
+        assert_eq!(
+            get_generator(7).generate().unwrap(),
+            get_generator(7).generate().unwrap()
+        );
+        assert_ne!(
+            get_generator(7).generate().unwrap(),
+            get_generator(8).generate().unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_color_distributions() {
+        for text in [
+            "red",
+            "red=3",
+            "red=5..2",
+            "red~-1",
+            "=0..2",
+            "dark red=0..2",
+        ] {
+            assert!(text.parse::<ColorDistribution>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn summarises_games() {
+        let games = get_games(EXAMPLE).unwrap();
+        let summaries = get_summary(&games);
+
+        let colors: Vec<Option<&str>> = summaries
+            .iter()
+            .map(|summary| summary.color.as_deref())
+            .collect();
+        assert_eq!(colors, vec![Some("blue"), Some("green"), Some("red"), None]);
+        // Blue cubes of the minimal bags: 6, 4, 6, 15 and 2.
+        assert_eq!(
+            summaries[0].minimal_bags,
+            Statistics {
+                min: 2,
+                mean: 6.6,
+                median: 6,
+                percentile_90: 15,
+                max: 15,
+            }
+        );
+        assert_eq!(summaries[3].draws.max, 8 + 6 + 20);
+    }
+
+    #[test]
+    fn writes_summaries_as_a_table() {
+        let games = get_games(EXAMPLE).unwrap();
+        let table = get_summary_table(&get_summary(&games));
+
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 2 + 2 * 4);
+        assert_eq!(
+            lines[0],
+            "Color        | Cubes of    |   Min |    Mean | Median |   P90 |   Max"
+        );
+        assert_eq!(
+            lines[3],
+            "blue         | minimal bag |     2 |    6.60 |      6 |    15 |    15"
+        );
+        assert_eq!(
+            lines[9],
+            "(all)        | minimal bag |     8 |   20.40 |     12 |    39 |    39"
+        );
+    }
+
+    #[test]
+    fn summarises_games_with_more_cubes_than_32_bits() {
+        let games = get_games("Game 1: 2147483647 red, 2147483647 blue\n").unwrap();
+        let summaries = get_summary(&games);
+        assert_eq!(summaries[2].color, None);
+        assert_eq!(summaries[2].draws.max, 4294967294);
+        assert_eq!(summaries[2].minimal_bags.mean, 4294967294.0);
+    }
 }
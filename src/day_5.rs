@@ -1,167 +1,379 @@
 use regex::Regex;
-use std::{cmp::min, collections::HashMap, fs, ops::Range};
+use std::{
+    cmp::{max, min},
+    collections::{HashMap, VecDeque},
+    fmt,
+    ops::Range,
+};
 
-pub fn solve(input_file: String) {
-    println!("### Solving Day 5  puzzle...###");
+use crate::{
+    error::{input_lines, Error, InputLine, Result},
+    solver::{Answer, Solver},
+};
 
-    let input = fs::read_to_string(input_file).unwrap();
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    /// Every map of the almanac, in the order of the input.
+    pub maps: Vec<CategoryMap>,
+    /// All the maps from seeds to locations composed into one, so that a seed or a range of
+    /// seeds is mapped in one step.
+    pub seed_to_location_map: Vec<MapElement>,
+}
 
-    let closest_location = part_1(&input);
+impl Solver for Almanac {
+    fn parse(input: &str) -> Result<Self> {
+        let maps = get_maps(input)?;
+        let seed_to_location_map =
+            get_composed_map_through_path(&get_path(&maps, "seed", "location")?);
+        return Ok(Almanac {
+            seeds: get_seeds(input)?,
+            maps,
+            seed_to_location_map,
+        });
+    }
 
-    println!("Part 1 Result: {closest_location}");
-    // let closest_location_in_seed_ranges = part_2(&input);
-    // println!("Part 2 Result: {closest_location_in_seed_ranges}");
-}
+    fn part_1(&self) -> Result<Answer> {
+        return Ok(part_1(self)?.into());
+    }
 
-#[derive(Debug)]
-struct MapElement {
-    destination_range_start: i64,
-    source_range_start: i64,
-    range_length: i64,
+    fn part_2(&self) -> Result<Answer> {
+        return Ok(part_2(self)?.into());
+    }
 }
 
-#[derive(Eq, PartialEq, Hash)]
-
-enum MapKind {
-    SeedToSoil,
-    SoilToFertilizer,
-    FertilizerToWater,
-    WaterToLight,
-    LightToTemperature,
-    TemperatureToHumidity,
-    HumidityToLocation,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapElement {
+    pub destination_range_start: i64,
+    pub source_range_start: i64,
+    pub range_length: i64,
 }
 
-fn part_1(input: &String) -> i64 {
-    let starter_seeds = input
-        .lines()
-        .next()
-        .unwrap()
-        .split(" ")
-        .collect::<Vec<&str>>()
-        .as_slice()[1..]
-        .to_vec();
+impl fmt::Display for MapElement {
+    /// Writes the element like a line of the almanac.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.destination_range_start, self.source_range_start, self.range_length
+        )
+    }
+}
 
-    let almanac: HashMap<MapKind, Vec<MapElement>> = get_almanac(&input);
+/// A section of the almanac, converting numbers of a category, e.g. seeds, into numbers of
+/// another category, e.g. soils.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryMap {
+    pub source: String,
+    pub destination: String,
+    pub elements: Vec<MapElement>,
+    /// Number of the line of the `<source>-to-<destination> map:` header.
+    pub line: usize,
+    /// Number of the line of each element.
+    pub element_lines: Vec<usize>,
+}
 
+fn part_1(almanac: &Almanac) -> Result<i64> {
     let mut locations: Vec<i64> = Vec::new();
-    for seed in starter_seeds.iter().map(|x| x.parse::<i64>().unwrap()) {
-        let location = get_location_from_seed(&almanac, seed);
+    for seed in almanac.seeds.iter() {
+        let location = get_destination_from_map(&almanac.seed_to_location_map, seed);
         locations.push(location);
     }
 
-    return locations.iter().min().unwrap().to_owned();
+    return locations.iter().min().copied().ok_or(Error::Puzzle {
+        day: 5,
+        message: "the almanac has no seeds".to_string(),
+    });
 }
 
-fn _part_2(input: &String) -> i64 {
-    let seed_ranges = _get_seed_ranges(input);
-    let almanac: HashMap<MapKind, Vec<MapElement>> = get_almanac(&input);
+fn part_2(almanac: &Almanac) -> Result<i64> {
+    let seed_ranges = get_seed_ranges(&almanac.seeds)?;
 
-    let mut current_closest_location = i64::MAX;
+    // Whole ranges of seeds go through the maps at once, instead of every seed one by one.
+    let mut closest_location: Option<i64> = None;
+    for seed_range in seed_ranges {
+        let location_ranges =
+            get_destination_ranges_from_map(&almanac.seed_to_location_map, seed_range);
+        for location_range in location_ranges {
+            closest_location = Some(min(
+                location_range.start,
+                closest_location.unwrap_or(i64::MAX),
+            ));
+        }
+    }
 
-    for range in seed_ranges {
-        println!("Current range: {:?}", range);
-        let end = range.end;
-        for seed in range {
-            if seed % 100000 == 0 {
-                println!("Current milestone: [{:?}/{:?}]", seed, end);
-            }
+    return closest_location.ok_or(Error::Puzzle {
+        day: 5,
+        message: "the almanac has no seeds".to_string(),
+    });
+}
+
+/// Seeds of the almanac, rejecting negative seeds and the pairs of range start and length whose
+/// range ends past 2^63.
+fn get_seeds(input: &str) -> Result<Vec<i64>> {
+    let seeds = get_unchecked_seeds(input)?;
+    let line = input_lines(5, input).next().unwrap();
+    let tokens: Vec<&str> = line.text.split(" ").skip(1).collect();
 
-            let location: i64 = get_location_from_seed(&almanac, seed);
-            current_closest_location = min(location, current_closest_location);
+    for (seed, token) in seeds.iter().zip(tokens.iter()) {
+        if let Some(message) = get_seed_problem(*seed) {
+            return Err(line.error(token, &message));
         }
     }
-
-    return current_closest_location;
+    for (index, seed_pair) in seeds.chunks(2).enumerate() {
+        if let Some(message) = get_seed_range_problem(seed_pair) {
+            return Err(line.error(tokens[2 * index], &message));
+        }
+    }
+    return Ok(seeds);
 }
 
-fn _get_seed_ranges(input: &String) -> Vec<Range<i64>> {
-    let binding = input
-        .lines()
-        .next()
-        .unwrap()
-        .split(" ")
-        .collect::<Vec<&str>>()
-        .as_slice()[1..]
-        .to_vec();
+fn get_unchecked_seeds(input: &str) -> Result<Vec<i64>> {
+    let Some(line) = input_lines(5, input).next() else {
+        return Err(Error::Puzzle {
+            day: 5,
+            message: "the almanac is empty".to_string(),
+        });
+    };
+
+    let mut tokens = line.text.split(" ");
+    let header = tokens.next().unwrap_or_default();
+    if header != "seeds:" {
+        return Err(line.error(header, "expected `seeds:`"));
+    }
+
+    return tokens
+        .map(|seed| line.parse::<i64>(seed, "a seed number"))
+        .collect();
+}
 
-    let mut seed_starters_and_ranges = binding.iter().peekable();
+pub fn get_seed_ranges(seeds: &[i64]) -> Result<Vec<Range<i64>>> {
+    // For part 2, the seeds line actually describes pairs of (range start, range length).
+    if seeds.len() % 2 != 0 {
+        return Err(Error::Puzzle {
+            day: 5,
+            message: "the seeds must come in pairs of range start and range length".to_string(),
+        });
+    }
 
     let mut seed_ranges: Vec<Range<i64>> = Vec::new();
-    while seed_starters_and_ranges.peek().is_some() {
-        println!("{:?}", seed_starters_and_ranges.peek());
-        let start = seed_starters_and_ranges
-            .next()
-            .unwrap()
-            .parse::<i64>()
-            .unwrap();
-        println!("{:?}", seed_starters_and_ranges.peek());
-        let length = seed_starters_and_ranges
-            .next()
-            .unwrap()
-            .parse::<i64>()
-            .unwrap();
+    for seed_starter_and_range in seeds.chunks(2) {
+        let start = seed_starter_and_range[0];
+        let length = seed_starter_and_range[1];
 
         seed_ranges.push(Range {
-            start: start,
+            start,
             end: start + length,
         });
     }
 
-    return seed_ranges;
+    return Ok(seed_ranges);
+}
+
+/// Location of a seed, going through the maps of the almanac one by one.
+pub fn get_location_from_seed(maps: &[CategoryMap], seed: i64) -> Result<i64> {
+    return get_number_in_category(maps, "seed", seed, "location");
+}
+
+/// Number of the `destination` category corresponding to a number of the `source` category,
+/// e.g. the fertilizer of seed 79.
+pub fn get_number_in_category(
+    maps: &[CategoryMap],
+    source: &str,
+    number: i64,
+    destination: &str,
+) -> Result<i64> {
+    let path = get_path(maps, source, destination)?;
+    return Ok(path.iter().fold(number, |number, map| {
+        return get_destination_from_map(&map.elements, &number);
+    }));
+}
+
+/// Maps to go through, in order, to convert numbers of the `source` category into numbers of the
+/// `destination` category.
+///
+/// Categories are the nodes of a graph whose edges are the maps, the path is a shortest one.
+pub fn get_path<'a>(
+    maps: &'a [CategoryMap],
+    source: &str,
+    destination: &str,
+) -> Result<Vec<&'a CategoryMap>> {
+    // Breadth-first search, remembering the map through which each category was first reached.
+    let mut reached_through: HashMap<&str, Option<&CategoryMap>> = HashMap::from([(source, None)]);
+    let mut categories_to_visit: VecDeque<&str> = VecDeque::from([source]);
+    while let Some(category) = categories_to_visit.pop_front() {
+        for map in maps.iter().filter(|map| map.source == category) {
+            if !reached_through.contains_key(map.destination.as_str()) {
+                reached_through.insert(&map.destination, Some(map));
+                categories_to_visit.push_back(&map.destination);
+            }
+        }
+    }
+
+    if !reached_through.contains_key(destination) {
+        return Err(Error::Puzzle {
+            day: 5,
+            message: format!("there is no way from {source} to {destination} in the almanac"),
+        });
+    }
+
+    let mut path: Vec<&CategoryMap> = Vec::new();
+    let mut category = destination;
+    while let Some(Some(map)) = reached_through.get(category) {
+        path.push(map);
+        category = &map.source;
+    }
+    path.reverse();
+    return Ok(path);
 }
 
-fn get_location_from_seed(almanac: &HashMap<MapKind, Vec<MapElement>>, seed: i64) -> i64 {
-    let soil: i64 = get_destination_from_map(&almanac[&MapKind::SeedToSoil], &seed);
-    let fertilizer: i64 = get_destination_from_map(&almanac[&MapKind::SoilToFertilizer], &soil);
-    let water: i64 = get_destination_from_map(&almanac[&MapKind::FertilizerToWater], &fertilizer);
-    let ligth: i64 = get_destination_from_map(&almanac[&MapKind::WaterToLight], &water);
-    let temperature: i64 = get_destination_from_map(&almanac[&MapKind::LightToTemperature], &ligth);
-    let humidity: i64 =
-        get_destination_from_map(&almanac[&MapKind::TemperatureToHumidity], &temperature);
-    let location: i64 = get_destination_from_map(&almanac[&MapKind::HumidityToLocation], &humidity);
+/// The map converting `source` into `destination`, if the almanac has one.
+pub fn get_map<'a>(
+    maps: &'a [CategoryMap],
+    source: &str,
+    destination: &str,
+) -> Option<&'a CategoryMap> {
+    return maps
+        .iter()
+        .find(|map| map.source == source && map.destination == destination);
+}
 
-    return location;
+/// Ranges of destinations of a range of sources through the maps of the path, which may be split
+/// in several pieces along the way.
+pub fn get_destination_ranges_through_path(
+    path: &[&CategoryMap],
+    source_range: Range<i64>,
+) -> Vec<Range<i64>> {
+    let mut ranges: Vec<Range<i64>> = vec![source_range];
+    for map in path {
+        ranges = ranges
+            .into_iter()
+            .flat_map(|range| get_destination_ranges_from_map(&map.elements, range))
+            .collect();
+    }
+    return ranges;
 }
 
-fn get_almanac(input: &String) -> HashMap<MapKind, Vec<MapElement>> {
-    let map_kinds: HashMap<&str, MapKind> = get_map_kinds();
-    let mut almanac: HashMap<MapKind, Vec<MapElement>> = HashMap::new();
+/// Parses every `<source>-to-<destination> map:` section following the seeds, rejecting the
+/// elements with negative numbers or ending past 2^63.
+///
+/// Every number of the maps is then between 0 and 2^63 - 1, and so is any difference between a
+/// source and its destination, even through several maps: the solutions can map numbers without
+/// checking every operation.
+pub fn get_maps(input: &str) -> Result<Vec<CategoryMap>> {
+    let maps = get_unchecked_maps(input)?;
+    for map in maps.iter() {
+        for (element, line) in map.elements.iter().zip(map.element_lines.iter()) {
+            if let Some((_, reason)) = get_element_problem(element) {
+                let line = input_lines(5, input).nth(line - 1).unwrap();
+                return Err(line.error(line.text, &format!("this element {reason}")));
+            }
+        }
+    }
+    return Ok(maps);
+}
+
+fn get_unchecked_maps(input: &str) -> Result<Vec<CategoryMap>> {
+    let header_regex = Regex::new(r"^(\w+)-to-(\w+) map:$").unwrap();
+    let mut maps: Vec<CategoryMap> = Vec::new();
+    let mut current_map: Option<CategoryMap> = None;
+
+    // The first line holds the seeds.
+    for line in input_lines(5, input).skip(1) {
+        if line.text.is_empty() {
+            // Once we land on a line break, we are at the end of a section.
+            maps.extend(current_map.take());
+            continue;
+        }
 
-    for (key, kind) in map_kinds {
-        let map = extract_map(input, key);
-        almanac.insert(kind, map);
+        if let Some(captures) = header_regex.captures(line.text) {
+            maps.extend(current_map.take());
+            let (_, [source, destination]) = captures.extract();
+            if let Some(map) = get_map(&maps, source, destination) {
+                let message = format!("this map is already defined on line {}", map.line);
+                return Err(line.error(line.text, &message));
+            }
+
+            current_map = Some(CategoryMap {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                elements: Vec::new(),
+                line: line.number,
+                element_lines: Vec::new(),
+            });
+            continue;
+        }
+
+        // Every line after the header is an element of the map.
+        let Some(map) = current_map.as_mut() else {
+            return Err(line.error(line.text, "expected `<source>-to-<destination> map:`"));
+        };
+        map.elements.push(extract_map_element(&line)?);
+        map.element_lines.push(line.number);
     }
 
-    return almanac;
+    maps.extend(current_map);
+    return Ok(maps);
+}
+
+fn extract_map_element(line: &InputLine) -> Result<MapElement> {
+    // Negative numbers are read, so that they can be pointed out rather than not recognised.
+    let re = Regex::new(r"^(-?\d+) (-?\d+) (-?\d+)$").unwrap();
+    let (_, [destination_range_start, source_range_start, range_length]) = re
+        .captures(line.text)
+        .ok_or_else(|| line.error(line.text, "expected `<destination> <source> <length>`"))?
+        .extract();
+
+    return Ok(MapElement {
+        destination_range_start: line.parse::<i64>(destination_range_start, "a range start")?,
+        source_range_start: line.parse::<i64>(source_range_start, "a range start")?,
+        range_length: line.parse::<i64>(range_length, "a range length")?,
+    });
 }
-fn get_map_kinds() -> HashMap<&'static str, MapKind> {
-    return HashMap::from([
-        ("seed-to-soil", MapKind::SeedToSoil),
-        ("soil-to-fertilizer", MapKind::SoilToFertilizer),
-        ("fertilizer-to-water", MapKind::FertilizerToWater),
-        ("water-to-light", MapKind::WaterToLight),
-        ("light-to-temperature", MapKind::LightToTemperature),
-        ("temperature-to-humidity", MapKind::TemperatureToHumidity),
-        ("humidity-to-location", MapKind::HumidityToLocation),
-    ]);
+
+/// Why the solutions cannot map the numbers of an element, if they cannot: a number is negative,
+/// or a range ends past 2^63. The reason reads after the element, e.g. `starts below 0`.
+fn get_element_problem(element: &MapElement) -> Option<(LintKind, String)> {
+    if element.range_length < 0 {
+        let message = format!(
+            "has a length of {}, so it maps nothing",
+            element.range_length
+        );
+        return Some((LintKind::EmptyRange, message));
+    }
+    if element.destination_range_start < 0 || element.source_range_start < 0 {
+        return Some((LintKind::Negative, "starts below 0".to_string()));
+    }
+    let source_end = element.source_range_start.checked_add(element.range_length);
+    let destination_end = element
+        .destination_range_start
+        .checked_add(element.range_length);
+    if source_end.is_none() || destination_end.is_none() {
+        return Some((LintKind::Overflow, "overflows 64-bit numbers".to_string()));
+    }
+    return None;
 }
 
-fn extract_map_element(line: &String) -> MapElement {
-    let re = Regex::new(r"(\d+) (\d+) (\d+)").unwrap();
-    let (_, [destination_range_start, source_range_start, range_length]) =
-        re.captures(line).unwrap().extract();
+fn get_seed_problem(seed: i64) -> Option<String> {
+    if seed < 0 {
+        return Some(format!("the seed number {seed} is below 0"));
+    }
+    return None;
+}
 
-    return MapElement {
-        destination_range_start: destination_range_start.parse::<i64>().unwrap(),
-        source_range_start: source_range_start.parse::<i64>().unwrap(),
-        range_length: range_length.parse::<i64>().unwrap(),
+/// Why a pair of seed range start and length cannot be a range of seeds, if it cannot.
+fn get_seed_range_problem(seed_pair: &[i64]) -> Option<String> {
+    let [start, length] = seed_pair else {
+        return None;
     };
+    if start.checked_add(*length).is_none() {
+        return Some(format!("the seed range {start} {length} ends past 2^63"));
+    }
+    return None;
 }
 
 fn is_contained_in(element: &MapElement, source: &i64) -> bool {
     return (element.source_range_start..element.source_range_start + element.range_length)
-        .contains(&source);
+        .contains(source);
 }
 
 fn get_destination(element: &MapElement, source: &i64) -> i64 {
@@ -173,10 +385,10 @@ fn get_destination(element: &MapElement, source: &i64) -> i64 {
     return source + difference;
 }
 
-fn get_destination_from_map(map: &Vec<MapElement>, source: &i64) -> i64 {
+pub fn get_destination_from_map(map: &Vec<MapElement>, source: &i64) -> i64 {
     for element in map {
-        if is_contained_in(&element, source) {
-            return get_destination(&element, source);
+        if is_contained_in(element, source) {
+            return get_destination(element, source);
         }
     }
 
@@ -185,26 +397,851 @@ fn get_destination_from_map(map: &Vec<MapElement>, source: &i64) -> i64 {
     return *source;
 }
 
-fn extract_map(input: &String, map_kind: &str) -> Vec<MapElement> {
-    let mut buffer = String::new();
-    let mut found_map = false;
-    let mut map_elements: Vec<MapElement> = Vec::new();
-    for line in input.lines() {
-        if found_map {
-            let is_empty_line = line == "";
-            if is_empty_line {
-                // Once we land on a line break, we are at the end of a section.
-                break;
+/// Destinations of a whole range of sources, split wherever the range crosses the boundary of an
+/// element of the map. The pieces come in no particular order.
+pub fn get_destination_ranges_from_map(
+    map: &Vec<MapElement>,
+    source_range: Range<i64>,
+) -> Vec<Range<i64>> {
+    return get_source_pieces(map, source_range)
+        .into_iter()
+        .map(|(range, difference)| range.start + difference..range.end + difference)
+        .collect();
+}
+
+/// Splits a range of sources into pieces that the map shifts by the same difference, along with
+/// that difference. The pieces come in no particular order.
+fn get_source_pieces(map: &Vec<MapElement>, source_range: Range<i64>) -> Vec<(Range<i64>, i64)> {
+    let mut pieces: Vec<(Range<i64>, i64)> = Vec::new();
+    // Pieces of the source range that no element has mapped yet.
+    let mut unmapped_ranges: Vec<Range<i64>> = vec![source_range];
+
+    for element in map {
+        let element_start = element.source_range_start;
+        let element_end = element.source_range_start + element.range_length;
+        let difference = element.destination_range_start - element.source_range_start;
+
+        let mut still_unmapped_ranges: Vec<Range<i64>> = Vec::new();
+        for range in unmapped_ranges {
+            let overlap = max(range.start, element_start)..min(range.end, element_end);
+            if overlap.is_empty() {
+                still_unmapped_ranges.push(range);
+                continue;
+            }
+
+            // Like for a single source, the first element containing a piece maps it.
+            let before = range.start..overlap.start;
+            let after = overlap.end..range.end;
+            pieces.push((overlap, difference));
+            still_unmapped_ranges.extend([before, after].into_iter().filter(|x| !x.is_empty()));
+        }
+        unmapped_ranges = still_unmapped_ranges;
+    }
+
+    // Any source numbers that aren't mapped correspond to the same destination number.
+    pieces.extend(
+        unmapped_ranges
+            .into_iter()
+            .filter(|x| !x.is_empty())
+            .map(|range| (range, 0)),
+    );
+    return pieces;
+}
+
+/// A single map equivalent to going through `first`, then through `second`.
+///
+/// Its elements do not overlap and are sorted by source, sources mapped to themselves are left out.
+pub fn get_composed_map(first: &Vec<MapElement>, second: &Vec<MapElement>) -> Vec<MapElement> {
+    // Outside of the sources of both maps, both map every number to itself.
+    let bounds = first.iter().chain(second.iter()).map(|element| {
+        return (
+            element.source_range_start,
+            element.source_range_start + element.range_length,
+        );
+    });
+    let start = bounds.clone().map(|(start, _)| start).min().unwrap_or(0);
+    let end = bounds.map(|(_, end)| end).max().unwrap_or(0);
+
+    let mut pieces: Vec<(Range<i64>, i64)> = Vec::new();
+    for (range, first_difference) in get_source_pieces(first, start..end) {
+        let destination_range = range.start + first_difference..range.end + first_difference;
+        for (second_range, second_difference) in get_source_pieces(second, destination_range) {
+            pieces.push((
+                second_range.start - first_difference..second_range.end - first_difference,
+                first_difference + second_difference,
+            ));
+        }
+    }
+    pieces.sort_by_key(|(range, _)| range.start);
+
+    let mut composed_map: Vec<MapElement> = Vec::new();
+    for (range, difference) in pieces {
+        if difference == 0 {
+            continue;
+        }
+        // Neighbouring pieces shifted by the same difference are one and the same element.
+        if let Some(last_element) = composed_map.last_mut() {
+            let last_end = last_element.source_range_start + last_element.range_length;
+            let last_difference =
+                last_element.destination_range_start - last_element.source_range_start;
+            if last_end == range.start && last_difference == difference {
+                last_element.range_length += range.end - range.start;
+                continue;
+            }
+        }
+        composed_map.push(MapElement {
+            destination_range_start: range.start + difference,
+            source_range_start: range.start,
+            range_length: range.end - range.start,
+        });
+    }
+    return composed_map;
+}
+
+/// Sources mapped into the range of destinations, sorted and merged: the inverse of the map.
+///
+/// Because of unmapped sources, several sources can end up at the same destination, so the
+/// inverse of a single destination can be several ranges of sources.
+pub fn get_source_ranges_from_map(
+    map: &Vec<MapElement>,
+    destination_range: Range<i64>,
+) -> Vec<Range<i64>> {
+    let start = map.iter().map(|x| x.source_range_start).min().unwrap_or(0);
+    let end = map
+        .iter()
+        .map(|x| x.source_range_start + x.range_length)
+        .max()
+        .unwrap_or(0);
+
+    // Outside of the sources of the map, every number is mapped to itself.
+    let mut source_ranges: Vec<Range<i64>> = vec![
+        destination_range.start..min(destination_range.end, start),
+        max(destination_range.start, end)..destination_range.end,
+    ];
+    for (range, difference) in get_source_pieces(map, start..end) {
+        let overlap = max(range.start + difference, destination_range.start)
+            ..min(range.end + difference, destination_range.end);
+        // Only a destination of the piece is sure to come from a source that fits in 64 bits.
+        if !overlap.is_empty() {
+            source_ranges.push(overlap.start - difference..overlap.end - difference);
+        }
+    }
+    return get_merged_ranges(source_ranges);
+}
+
+/// Sources whose destinations through the maps of the path are in the range, sorted and merged:
+/// the inverse of the path.
+pub fn get_source_ranges_through_path(
+    path: &[&CategoryMap],
+    destination_range: Range<i64>,
+) -> Vec<Range<i64>> {
+    let mut ranges: Vec<Range<i64>> = vec![destination_range];
+    for map in path.iter().rev() {
+        ranges = get_merged_ranges(
+            ranges
+                .into_iter()
+                .flat_map(|range| get_source_ranges_from_map(&map.elements, range))
+                .collect(),
+        );
+    }
+    return ranges;
+}
+
+/// Seeds whose location is in the range, sorted and merged: the inverse of the whole almanac.
+///
+/// The range must not be empty, which is most likely a mistake of whoever asked.
+pub fn get_seed_ranges_from_location_range(
+    maps: &[CategoryMap],
+    location_range: Range<i64>,
+) -> Result<Vec<Range<i64>>> {
+    if location_range.is_empty() {
+        return Err(Error::Puzzle {
+            day: 5,
+            message: format!(
+                "expected the range of locations to end after its start, found {}..{}",
+                location_range.start, location_range.end
+            ),
+        });
+    }
+    let path = get_path(maps, "seed", "location")?;
+    return Ok(get_source_ranges_through_path(&path, location_range));
+}
+
+/// Sorts the ranges and merges the ones that overlap or touch, leaving out the empty ones.
+fn get_merged_ranges(mut ranges: Vec<Range<i64>>) -> Vec<Range<i64>> {
+    ranges.retain(|x| !x.is_empty());
+    ranges.sort_by_key(|x| x.start);
+
+    let mut merged_ranges: Vec<Range<i64>> = Vec::new();
+    for range in ranges {
+        match merged_ranges.last_mut() {
+            Some(last_range) if range.start <= last_range.end => {
+                last_range.end = max(last_range.end, range.end);
+            }
+            _ => merged_ranges.push(range),
+        }
+    }
+    return merged_ranges;
+}
+
+/// Part 2 solved backwards: locations are scanned upward until one comes from a seed of the
+/// seed ranges.
+///
+/// Locations are first checked by blocks, then one by one inside the first block having a seed.
+pub fn get_closest_location_by_reverse_search(almanac: &Almanac) -> Result<i64> {
+    const BLOCK_LENGTH: i64 = 1 << 16;
+    let seed_ranges = get_seed_ranges(&almanac.seeds)?;
+    let path = get_path(&almanac.maps, "seed", "location")?;
+    let is_from_a_seed = |location_range: Range<i64>| -> bool {
+        return get_source_ranges_through_path(&path, location_range)
+            .iter()
+            .any(|range| {
+                return seed_ranges
+                    .iter()
+                    .any(|seeds| range.start < seeds.end && seeds.start < range.end);
+            });
+    };
+
+    // No location is past the last seed or the last destination of a map.
+    let last_location = seed_ranges
+        .iter()
+        .map(|range| range.end)
+        .chain(path.iter().flat_map(|map| &map.elements).map(|element| {
+            return element.destination_range_start + element.range_length;
+        }))
+        .max()
+        .unwrap_or(0);
+
+    let mut block_start = 0;
+    while block_start < last_location {
+        let block_end = block_start.saturating_add(BLOCK_LENGTH);
+        if is_from_a_seed(block_start..block_end) {
+            let location = (block_start..block_end)
+                .find(|location| is_from_a_seed(*location..location + 1))
+                .unwrap();
+            return Ok(location);
+        }
+        block_start = block_end;
+    }
+
+    return Err(Error::Puzzle {
+        day: 5,
+        message: "the almanac has no seeds".to_string(),
+    });
+}
+
+/// A single map equivalent to going through every map of the path.
+pub fn get_composed_map_through_path(path: &[&CategoryMap]) -> Vec<MapElement> {
+    let mut composed_map: Vec<MapElement> = Vec::new();
+    for map in path {
+        composed_map = get_composed_map(&composed_map, &map.elements);
+    }
+    return composed_map;
+}
+
+/// What kind of problem a lint points out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    /// Two elements of a map have overlapping sources, only the first one maps them.
+    Overlap,
+    /// Sources between the elements of a map that are not mapped, and keep their number.
+    /// This is allowed by the puzzle, so it is only a note.
+    Gap,
+    /// An element whose length is zero or negative, which maps nothing.
+    EmptyRange,
+    /// A map that cannot be reached from the seeds, or locations that cannot be reached at all.
+    Unreachable,
+    /// Numbers whose computation overflows 64 bits.
+    Overflow,
+    /// Numbers below 0, which the almanac never has and the solutions do not handle.
+    Negative,
+    /// A last seed without a range length, which part 2 cannot pair.
+    UnpairedSeed,
+}
+
+impl LintKind {
+    /// Whether the problem makes the almanac wrong, rather than only surprising.
+    pub fn is_error(&self) -> bool {
+        return *self != LintKind::Gap;
+    }
+}
+
+/// A problem found in an almanac.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub line: usize,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.kind.is_error() {
+            "error"
+        } else {
+            "note"
+        };
+        write!(f, "line {}: {severity}: {}", self.line, self.message)
+    }
+}
+
+/// Checks an almanac for anything that the solutions would silently get wrong or ignore, in the
+/// order of the lines.
+///
+/// The almanac must still be well-formed, otherwise the parse error is returned instead.
+pub fn get_lints(input: &str) -> Result<Vec<Lint>> {
+    // The numbers that the solutions reject are reported as lints, along with the other ones.
+    let seeds = get_unchecked_seeds(input)?;
+    let maps = get_unchecked_maps(input)?;
+    let mut lints: Vec<Lint> = Vec::new();
+
+    for seed in seeds.iter() {
+        if let Some(message) = get_seed_problem(*seed) {
+            lints.push(Lint {
+                line: 1,
+                kind: LintKind::Negative,
+                message,
+            });
+        }
+    }
+    for seed_pair in seeds.chunks(2) {
+        if let Some(message) = get_seed_range_problem(seed_pair) {
+            lints.push(Lint {
+                line: 1,
+                kind: LintKind::Overflow,
+                message,
+            });
+        }
+    }
+    if seeds.len() % 2 != 0 {
+        let last_seed = seeds[seeds.len() - 1];
+        lints.push(Lint {
+            line: 1,
+            kind: LintKind::UnpairedSeed,
+            message: format!(
+                "the last seed {last_seed} has no range length, but part 2 reads the seeds in pairs"
+            ),
+        });
+    }
+
+    for map in maps.iter() {
+        lints.extend(get_map_lints(map));
+    }
+    lints.extend(get_reachability_lints(&maps));
+
+    lints.sort_by_key(|lint| lint.line);
+    return Ok(lints);
+}
+
+fn get_map_lints(map: &CategoryMap) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = Vec::new();
+    // Sources of the elements that map something, with the line of the element.
+    let mut source_ranges: Vec<(Range<i64>, usize)> = Vec::new();
+
+    for (element, line) in map.elements.iter().zip(map.element_lines.iter().copied()) {
+        if let Some((kind, reason)) = get_element_problem(element) {
+            lints.push(Lint {
+                line,
+                kind,
+                message: format!("the element {element} {reason}"),
+            });
+            continue;
+        }
+        if element.range_length == 0 {
+            lints.push(Lint {
+                line,
+                kind: LintKind::EmptyRange,
+                message: format!("the element {element} has a length of 0, so it maps nothing"),
+            });
+            continue;
+        }
+
+        let source_range =
+            element.source_range_start..element.source_range_start + element.range_length;
+        for (other_range, other_line) in source_ranges.iter() {
+            let overlap =
+                max(source_range.start, other_range.start)..min(source_range.end, other_range.end);
+            if !overlap.is_empty() {
+                lints.push(Lint {
+                    line,
+                    kind: LintKind::Overlap,
+                    message: format!(
+                        "the sources {}..{} overlap the ones of line {other_line}, which wins for {}..{}",
+                        source_range.start, source_range.end, overlap.start, overlap.end
+                    ),
+                });
+            }
+        }
+        source_ranges.push((source_range, line));
+    }
+
+    let merged_ranges =
+        get_merged_ranges(source_ranges.into_iter().map(|(range, _)| range).collect());
+    for pair in merged_ranges.windows(2) {
+        lints.push(Lint {
+            line: map.line,
+            kind: LintKind::Gap,
+            message: format!(
+                "the {} sources {}..{} are not mapped, they keep their number",
+                map.source, pair[0].end, pair[1].start
+            ),
+        });
+    }
+    return lints;
+}
+
+/// Maps that the seeds never go through, and whether the seeds can get to a location at all.
+fn get_reachability_lints(maps: &[CategoryMap]) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = Vec::new();
+    for map in maps.iter() {
+        if get_path(maps, "seed", &map.source).is_err() {
+            lints.push(Lint {
+                line: map.line,
+                kind: LintKind::Unreachable,
+                message: format!(
+                    "no map leads from seed to {}, so this map is never used",
+                    map.source
+                ),
+            });
+        }
+    }
+    if let Err(Error::Puzzle { message, .. }) = get_path(maps, "seed", "location") {
+        lints.push(Lint {
+            line: 1,
+            kind: LintKind::Unreachable,
+            message,
+        });
+    }
+    return lints;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn solves_part_1_example() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        assert_eq!(almanac.part_1().unwrap(), Answer::from(35));
+    }
+
+    #[test]
+    fn solves_part_2_example() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        assert_eq!(almanac.part_2().unwrap(), Answer::from(46));
+    }
+
+    #[test]
+    fn finds_location_of_each_seed() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let locations: Vec<i64> = almanac
+            .seeds
+            .iter()
+            .map(|seed| get_location_from_seed(&almanac.maps, *seed).unwrap())
+            .collect();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn keeps_unmapped_sources() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let seed_to_soil = &get_map(&almanac.maps, "seed", "soil").unwrap().elements;
+        assert_eq!(get_destination_from_map(seed_to_soil, &10), 10);
+        assert_eq!(get_destination_from_map(seed_to_soil, &98), 50);
+        assert_eq!(get_destination_from_map(seed_to_soil, &53), 55);
+    }
+
+    #[test]
+    fn splits_ranges_at_map_boundaries() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let seed_to_soil = &get_map(&almanac.maps, "seed", "soil").unwrap().elements;
+
+        let mut soil_ranges = get_destination_ranges_from_map(seed_to_soil, 40..100);
+        soil_ranges.sort_by_key(|x| x.start);
+        assert_eq!(soil_ranges, vec![40..50, 50..52, 52..100]);
+        assert_eq!(
+            get_destination_ranges_from_map(seed_to_soil, 10..20),
+            vec![10..20]
+        );
+        assert!(get_destination_ranges_from_map(seed_to_soil, 20..20).is_empty());
+    }
+
+    #[test]
+    fn maps_ranges_like_every_seed_of_them() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let path = get_path(&almanac.maps, "seed", "location").unwrap();
+        for seed_range in [0..100, 79..93, 55..68, 13..14] {
+            let mut locations: Vec<i64> =
+                get_destination_ranges_through_path(&path, seed_range.clone())
+                    .into_iter()
+                    .flatten()
+                    .collect();
+            locations.sort();
+
+            let mut expected_locations: Vec<i64> = seed_range
+                .map(|seed| get_location_from_seed(&almanac.maps, seed).unwrap())
+                .collect();
+            expected_locations.sort();
+            assert_eq!(locations, expected_locations);
+        }
+    }
+
+    #[test]
+    fn composes_two_maps() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let seed_to_soil = &get_map(&almanac.maps, "seed", "soil").unwrap().elements;
+        let soil_to_fertilizer = &get_map(&almanac.maps, "soil", "fertilizer")
+            .unwrap()
+            .elements;
+        let seed_to_fertilizer = get_composed_map(seed_to_soil, soil_to_fertilizer);
+
+        for seed in -10..200 {
+            let soil = get_destination_from_map(seed_to_soil, &seed);
+            let fertilizer = get_destination_from_map(soil_to_fertilizer, &soil);
+            assert_eq!(
+                get_destination_from_map(&seed_to_fertilizer, &seed),
+                fertilizer
+            );
+        }
+        let mut sorted_seed_to_soil = seed_to_soil.clone();
+        sorted_seed_to_soil.sort_by_key(|element| element.source_range_start);
+        assert_eq!(
+            get_composed_map(&Vec::new(), seed_to_soil),
+            sorted_seed_to_soil
+        );
+    }
+
+    #[test]
+    fn composes_the_whole_almanac() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        for seed in -10..200 {
+            assert_eq!(
+                get_destination_from_map(&almanac.seed_to_location_map, &seed),
+                get_location_from_seed(&almanac.maps, seed).unwrap()
+            );
+        }
+
+        let sources: Vec<i64> = almanac
+            .seed_to_location_map
+            .iter()
+            .map(|element| element.source_range_start)
+            .collect();
+        assert!(sources.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(almanac.seed_to_location_map[0].to_string(), "22 0 14");
+    }
+
+    #[test]
+    fn inverts_a_map() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let seed_to_soil = &get_map(&almanac.maps, "seed", "soil").unwrap().elements;
+
+        assert_eq!(
+            get_source_ranges_from_map(seed_to_soil, 50..52),
+            vec![98..100]
+        );
+        assert_eq!(
+            get_source_ranges_from_map(seed_to_soil, 10..20),
+            vec![10..20]
+        );
+        assert_eq!(
+            get_source_ranges_from_map(seed_to_soil, 45..55),
+            // Unmapped seeds 45 to 49, then seeds 50 to 52 shifted by 2, and seeds 98 and 99.
+            vec![45..53, 98..100]
+        );
+    }
+
+    #[test]
+    fn inverts_the_whole_almanac() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        for location_range in [46..47, 40..60, 0..10, 95..120] {
+            let seed_ranges =
+                get_seed_ranges_from_location_range(&almanac.maps, location_range.clone()).unwrap();
+
+            for seed in -10..200 {
+                let location = get_location_from_seed(&almanac.maps, seed).unwrap();
+                assert_eq!(
+                    seed_ranges.iter().any(|range| range.contains(&seed)),
+                    location_range.contains(&location),
+                    "seed {seed} at location {location}"
+                );
             }
-            // We push every line after the header into a buffer.
-            buffer.push_str((line.to_owned() + "\n").as_str());
-            map_elements.push(extract_map_element(&line.to_string()))
         }
-        if line.contains(map_kind) {
-            found_map = true;
+    }
+
+    #[test]
+    fn rejects_empty_ranges_of_locations() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        for (start, end) in [(46, 46), (46, 40)] {
+            assert!(matches!(
+                get_seed_ranges_from_location_range(&almanac.maps, start..end),
+                Err(Error::Puzzle { day: 5, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn solves_part_2_example_backwards() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            get_closest_location_by_reverse_search(&almanac).unwrap(),
+            46
+        );
+    }
+
+    #[test]
+    fn follows_the_headers_of_the_almanac() {
+        // Categories in another order, an extra one, and a shortcut from seeds to water.
+        let input = "seeds: 79 14
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+seed-to-water map:
+1 2 3
+
+fertilizer-to-location map:
+10 0 100
+
+location-to-planet map:
+0 0 1
+";
+        let almanac = Almanac::parse(input).unwrap();
+        let categories: Vec<(&str, &str)> = get_path(&almanac.maps, "seed", "planet")
+            .unwrap()
+            .iter()
+            .map(|map| (map.source.as_str(), map.destination.as_str()))
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                ("seed", "soil"),
+                ("soil", "fertilizer"),
+                ("fertilizer", "location"),
+                ("location", "planet")
+            ]
+        );
+
+        // Seed 79 goes to soil 81, fertilizer 81 and location 91, seed 14 to soil 14,
+        // fertilizer 53 and location 63.
+        assert_eq!(
+            get_number_in_category(&almanac.maps, "seed", 79, "fertilizer").unwrap(),
+            81
+        );
+        assert_eq!(almanac.part_1().unwrap(), Answer::from(63));
+        assert_eq!(
+            get_number_in_category(&almanac.maps, "soil", 7, "soil").unwrap(),
+            7
+        );
+        assert!(matches!(
+            get_number_in_category(&almanac.maps, "water", 7, "seed"),
+            Err(Error::Puzzle { .. })
+        ));
+    }
+
+    #[test]
+    fn finds_the_fertilizer_of_a_seed() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let fertilizer = get_number_in_category(&almanac.maps, "seed", 79, "fertilizer");
+        assert_eq!(fertilizer.unwrap(), 81);
+        let humidity = get_number_in_category(&almanac.maps, "soil", 81, "humidity");
+        assert_eq!(humidity.unwrap(), 78);
+    }
+
+    #[test]
+    fn rejects_malformed_almanacs() {
+        let cases = [
+            (
+                "seeds: 1\n\n1 2 3\n",
+                3,
+                "expected `<source>-to-<destination> map:`",
+            ),
+            (
+                "seeds: 1\n\nseed-to-soil map:\n1 2 3\n\nseed-to-soil map:\n",
+                6,
+                "this map is already defined on line 3",
+            ),
+            (
+                "seeds: 1\n\nseed-to-soil map:\n1 2\n",
+                4,
+                "expected `<destination> <source> <length>`",
+            ),
+            (
+                "seeds: 1\n\nseed-to-soil map:\n1 2 3\n0 9223372036854775800 100\n",
+                5,
+                "this element overflows 64-bit numbers",
+            ),
+            (
+                "seeds: 1\n\nseed-to-soil map:\n5 20 -3\n",
+                4,
+                "this element has a length of -3, so it maps nothing",
+            ),
+            (
+                "seeds: 1\n\nseed-to-soil map:\n-5 20 3\n",
+                4,
+                "this element starts below 0",
+            ),
+        ];
+        for (input, expected_line, expected_message) in cases {
+            let Err(Error::Parse { line, message, .. }) = get_maps(input) else {
+                panic!("{input:?} should not parse");
+            };
+            assert_eq!((line, message.as_str()), (expected_line, expected_message));
+        }
+    }
+
+    #[test]
+    fn rejects_seeds_out_of_range() {
+        let cases = [
+            ("seeds: 79 14 -55 13", 14, "the seed number -55 is below 0"),
+            (
+                "seeds: 79 14 9223372036854775800 100",
+                14,
+                "the seed range 9223372036854775800 100 ends past 2^63",
+            ),
+        ];
+        for (input, expected_column, expected_message) in cases {
+            let Err(Error::Parse {
+                line,
+                column,
+                message,
+                ..
+            }) = get_seeds(input)
+            else {
+                panic!("{input:?} should not parse");
+            };
+            assert_eq!(
+                (line, column, message.as_str()),
+                (1, expected_column, expected_message)
+            );
         }
-        // We continue until we find the section we want
     }
-    // println!("Extracted map \n{:?}", buffer);
-    return map_elements;
+
+    #[test]
+    fn solves_almanacs_up_to_the_last_64_bit_number() {
+        let input = "seeds: 9223372036854775000 807 0 5
+
+seed-to-soil map:
+9223372036854775000 0 800
+0 9223372036854775000 807
+
+soil-to-location map:
+9223372036854775800 9223372036854775800 7
+0 10 3
+";
+        let almanac = Almanac::parse(input).unwrap();
+        assert_eq!(almanac.part_1().unwrap(), Answer::from(0));
+        assert_eq!(almanac.part_2().unwrap(), Answer::from(0));
+        // Only seed 799 ends up at soil 9223372036854775799 or past, where soils are their own locations.
+        assert_eq!(
+            get_seed_ranges_from_location_range(&almanac.maps, 9223372036854775799..i64::MAX)
+                .unwrap(),
+            vec![799..800]
+        );
+    }
+
+    #[test]
+    fn finds_nothing_wrong_with_the_example() {
+        let lints = get_lints(EXAMPLE).unwrap();
+        assert!(lints.iter().all(|lint| !lint.kind.is_error()), "{lints:?}");
+    }
+
+    #[test]
+    fn lints_almanacs() {
+        let input = "seeds: 79 14 9223372036854775807 1
+
+seed-to-soil map:
+50 98 2
+52 50 48
+60 90 20
+1 2 0
+
+soil-to-location map:
+0 0 10
+5 20 -3
+0 9223372036854775800 100
+20 30 5
+
+water-to-light map:
+0 1 2
+";
+        let lints: Vec<String> = get_lints(input)
+            .unwrap()
+            .iter()
+            .map(|lint| lint.to_string())
+            .collect();
+        assert_eq!(
+            lints,
+            vec![
+                "line 1: error: the seed range 9223372036854775807 1 ends past 2^63",
+                "line 6: error: the sources 90..110 overlap the ones of line 4, which wins for 98..100",
+                "line 6: error: the sources 90..110 overlap the ones of line 5, which wins for 90..98",
+                "line 7: error: the element 1 2 0 has a length of 0, so it maps nothing",
+                "line 9: note: the soil sources 10..30 are not mapped, they keep their number",
+                "line 11: error: the element 5 20 -3 has a length of -3, so it maps nothing",
+                "line 12: error: the element 0 9223372036854775800 100 overflows 64-bit numbers",
+                "line 15: error: no map leads from seed to water, so this map is never used",
+            ]
+        );
+    }
+
+    #[test]
+    fn lints_unpaired_seeds() {
+        let input = "seeds: 79 14 55
+
+seed-to-location map:
+0 0 100
+";
+        let lints: Vec<String> = get_lints(input)
+            .unwrap()
+            .iter()
+            .map(|lint| lint.to_string())
+            .collect();
+        assert_eq!(
+            lints,
+            vec![
+                "line 1: error: the last seed 55 has no range length, but part 2 reads the seeds in pairs"
+            ]
+        );
+    }
 }
@@ -1,47 +1,61 @@
-use std::{fs, iter::zip};
+use std::iter::zip;
 
-pub fn solve(input_file: String) {
-    println!("########## Solving Day 6  puzzle...##########\n");
+use crate::{
+    error::{input_lines, Error, InputLine, Result},
+    solver::{Answer, Solver},
+};
 
-    let input = fs::read_to_string(input_file).unwrap();
+pub struct Races {
+    pub race_times: Vec<i64>,
+    pub record_distances: Vec<i64>,
+}
 
-    let output_part_1 = part_1(&input);
+impl Solver for Races {
+    fn parse(input: &str) -> Result<Self> {
+        let mut lines = input_lines(6, input);
+        let (Some(times_line), Some(distances_line)) = (lines.next(), lines.next()) else {
+            return Err(Error::Puzzle {
+                day: 6,
+                message: "expected a line of race times and a line of record distances".to_string(),
+            });
+        };
+
+        let race_times = get_numbers(&times_line)?;
+        let record_distances = get_numbers(&distances_line)?;
+        if race_times.len() != record_distances.len() {
+            return Err(distances_line.error(
+                distances_line.text,
+                &format!("expected {} record distances", race_times.len()),
+            ));
+        }
 
-    println!("Part 1 Result: {output_part_1}\n");
-    let output_part_2 = part_2(&input);
+        return Ok(Races {
+            race_times,
+            record_distances,
+        });
+    }
+
+    fn part_1(&self) -> Result<Answer> {
+        return Ok(part_1(&self.race_times, &self.record_distances).into());
+    }
 
-    println!("\nPart 2 Result: {output_part_2}");
+    fn part_2(&self) -> Result<Answer> {
+        return Ok(part_2(&self.race_times, &self.record_distances)?.into());
+    }
 }
 
-fn part_1(input: &String) -> i64 {
-    let mut lines = input.lines();
-    let race_times: Vec<i64> = lines
-        .next()
-        .unwrap()
-        .split(":")
-        .collect::<Vec<&str>>()
-        .as_slice()[1]
-        .split(" ")
-        .map(|x| x.parse::<i64>().ok())
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .collect();
+fn get_numbers(line: &InputLine) -> Result<Vec<i64>> {
+    let Some((_, numbers)) = line.text.split_once(":") else {
+        return Err(line.error(line.text, "expected `<label>: <numbers>`"));
+    };
 
-    let record_distances: Vec<i64> = lines
-        .next()
-        .unwrap()
-        .split(":")
-        .collect::<Vec<&str>>()
-        .as_slice()[1]
-        .split(" ")
-        .map(|x| x.parse::<i64>().ok())
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
+    return numbers
+        .split_whitespace()
+        .map(|number| line.parse::<i64>(number, "a number"))
         .collect();
+}
 
-    // println!("{:?}", race_times);
-    // println!("{:?}", record_distances);
-
+fn part_1(race_times: &[i64], record_distances: &[i64]) -> i64 {
     let mut result = 1;
 
     for (race_time, record_distance) in zip(race_times, record_distances) {
@@ -50,68 +64,36 @@ fn part_1(input: &String) -> i64 {
         //     record_distance as i64,
         // );
         let _nb_ways_naive =
-            _get_numbers_of_ways_one_can_beat_the_record_naive(race_time, record_distance as i64);
+            _get_numbers_of_ways_one_can_beat_the_record_naive(*race_time, *record_distance);
         // println!("nb_ways_optimized: {nb_ways_optimized}");
         // println!("nb_ways_naive: {nb_ways_naive}");
         result *= _nb_ways_naive;
-
     }
 
-    return result as i64;
+    return result;
 }
 
-fn part_2(input: &String) -> i64 {
-    let mut lines = input.lines();
-    // let time: Vec<i64> = lines
-    let race_times: Vec<i64> = lines
-        .next()
-        .unwrap()
-        .split(":")
-        .collect::<Vec<&str>>()
-        .as_slice()[1]
-        .split(" ")
-        .map(|x| x.parse::<i64>().ok())
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .collect();
+fn part_2(race_times: &[i64], record_distances: &[i64]) -> Result<i64> {
+    let race_time_without_kerning = get_number_without_kerning(race_times)?;
+    let record_distance_without_kerning = get_number_without_kerning(record_distances)?;
 
-    let record_distances: Vec<i64> = lines
-        .next()
-        .unwrap()
-        .split(":")
-        .collect::<Vec<&str>>()
-        .as_slice()[1]
-        .split(" ")
-        .map(|x| x.parse::<i64>().ok())
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .collect();
+    return Ok(_get_numbers_of_ways_one_can_beat_the_record_naive(
+        race_time_without_kerning,
+        record_distance_without_kerning,
+    ));
+}
 
-    let race_time_without_kerning = race_times
+fn get_number_without_kerning(numbers: &[i64]) -> Result<i64> {
+    let digits = numbers
         .iter()
         .map(|x| x.to_string())
         .collect::<Vec<String>>()
-        .join("")
-        .parse::<i64>()
-        .unwrap();
-    let record_distance_without_kerning = record_distances
-        .iter()
-        .map(|x| x.to_string())
-        .collect::<Vec<String>>()
-        .join("")
-        .parse::<i64>()
-        .unwrap();
-
-    println!("race_time_without_kerning: {:?}", race_time_without_kerning);
-    println!(
-        "record_distance_without_kerning: {:?}",
-        record_distance_without_kerning
-    );
+        .join("");
 
-    return _get_numbers_of_ways_one_can_beat_the_record_naive(
-        race_time_without_kerning as i64,
-        record_distance_without_kerning,
-    );
+    return digits.parse::<i64>().map_err(|_| Error::Puzzle {
+        day: 6,
+        message: format!("{digits:?} is not a valid race once the kerning is removed"),
+    });
 }
 fn _get_numbers_of_ways_one_can_beat_the_record_naive(race_time: i64, distance_record: i64) -> i64 {
     let mut distances_higher_than_records: Vec<i64> = Vec::new();
@@ -133,7 +115,7 @@ fn _get_numbers_of_ways_one_can_beat_the_record_naive(race_time: i64, distance_r
     return number_of_ways_one_can_beat_the_record as i64;
 }
 
-fn get_numbers_of_ways_one_can_beat_the_record_optimized(
+fn _get_numbers_of_ways_one_can_beat_the_record_optimized(
     race_time: i64,
     distance_record: i64,
 ) -> i64 {
@@ -148,7 +130,7 @@ fn get_numbers_of_ways_one_can_beat_the_record_optimized(
         pivot = (start_index + stop_index) / 2;
         let time_remaining_to_move = race_time - pivot;
         let speed: i64 = pivot;
-        distance_travelled = (time_remaining_to_move as i64 * speed as i64) as i64;
+        distance_travelled = time_remaining_to_move * speed;
 
         if distance_travelled > distance_record {
             stop_index = pivot - 1;
@@ -192,3 +174,38 @@ fn get_numbers_of_ways_one_can_beat_the_record_optimized(
 
     return nb_ways;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200
+";
+
+    #[test]
+    fn solves_part_1_example() {
+        let races = Races::parse(EXAMPLE).unwrap();
+        assert_eq!(races.part_1().unwrap(), Answer::from(288));
+    }
+
+    #[test]
+    fn solves_part_2_example() {
+        let races = Races::parse(EXAMPLE).unwrap();
+        assert_eq!(races.part_2().unwrap(), Answer::from(71503));
+    }
+
+    #[test]
+    fn counts_ways_to_beat_each_record() {
+        for (race_time, distance_record, nb_ways) in [(7, 9, 4), (15, 40, 8), (30, 200, 9)] {
+            assert_eq!(
+                _get_numbers_of_ways_one_can_beat_the_record_naive(race_time, distance_record),
+                nb_ways
+            );
+            assert_eq!(
+                _get_numbers_of_ways_one_can_beat_the_record_optimized(race_time, distance_record),
+                nb_ways
+            );
+        }
+    }
+}
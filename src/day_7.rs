@@ -1,54 +1,65 @@
-use std::{cmp::Ordering, collections::HashMap, fs, iter::zip};
+use std::{cmp::Ordering, collections::HashMap, iter::zip};
 
-pub fn solve(input_file: String) {
-    println!("########## Solving Day 7  puzzle...##########\n");
+use crate::{
+    error::{input_lines, Result},
+    solver::{Answer, Solver},
+};
 
-    let input = fs::read_to_string(input_file).unwrap();
+const CARDS: &str = "23456789TJQKA";
 
-    let output_part_1 = _solve(&input, &compare_hands_part_1);
-    println!("Part 1 Result: {output_part_1}");
-
-    let output_part_2: i64 = _solve(&input, &compare_hands_part_2);
-    println!("\nPart 2 Result: {output_part_2}");
+pub struct CamelCards {
+    pub hands: Vec<String>,
+    pub bids: Vec<i32>,
 }
 
-fn _solve(input: &String, compare_fn: &dyn Fn(&str, &str) -> i8) -> i64 {
-    let hands = input
-        .lines()
-        .map(|line| line.split(" ").nth(0).unwrap())
-        .collect::<Vec<&str>>();
-    let bids = input
-        .lines()
-        .map(|line| line.split(" ").nth(1).unwrap())
-        .map(|bid| bid.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>();
+impl Solver for CamelCards {
+    fn parse(input: &str) -> Result<Self> {
+        let mut hands: Vec<String> = Vec::new();
+        let mut bids: Vec<i32> = Vec::new();
+
+        for line in input_lines(7, input) {
+            let mut tokens = line.text.split(" ");
+            let hand = tokens.next().unwrap_or_default();
+            if hand.chars().count() != 5 || !hand.chars().all(|card| CARDS.contains(card)) {
+                return Err(line.error(hand, &format!("expected a hand of 5 cards among {CARDS}")));
+            }
+
+            let bid = tokens.next().unwrap_or_default();
+            hands.push(hand.to_string());
+            bids.push(line.parse::<i32>(bid, "a bid")?);
+        }
+
+        return Ok(CamelCards { hands, bids });
+    }
+
+    fn part_1(&self) -> Result<Answer> {
+        return Ok(_solve(&self.hands, &self.bids, &compare_hands_part_1).into());
+    }
 
+    fn part_2(&self) -> Result<Answer> {
+        return Ok(_solve(&self.hands, &self.bids, &compare_hands_part_2).into());
+    }
+}
+
+fn _solve(hands: &[String], bids: &[i32], compare_fn: &dyn Fn(&str, &str) -> i8) -> i64 {
     // We are going to sort the hands by their rank, from weakest (rank 1) to strongest (rank N)
     let compare_ordering = |hand_1: &str, hand_2: &str| -> Ordering {
         match compare_fn(hand_1, hand_2) {
             -1 => Ordering::Greater,
             0 => Ordering::Equal,
-            1 => Ordering::Less,
-            _ => panic!(),
+            _ => Ordering::Less,
         }
     };
 
-    let hands_to_bids: HashMap<&str, i32> =
-        hands.iter().cloned().zip(bids.iter().cloned()).collect();
-
-    let mut sorted_hands = hands.to_owned();
-    sorted_hands.sort_by(|hand_1, hand_2| compare_ordering(hand_1, hand_2));
-
-    // for (h, h_sort) in zip(hands, &sorted_hands) {
-    //     println!("{:?}, {:?}", h, h_sort);
-    // }
+    // The bids stay next to their hand while sorting, so that identical hands keep their own bid.
+    let mut sorted_hands_and_bids = zip(hands, bids).collect::<Vec<(&String, &i32)>>();
+    sorted_hands_and_bids.sort_by(|(hand_1, _), (hand_2, _)| compare_ordering(hand_1, hand_2));
 
-    let mut total_winnings = 0 as i64;
+    let mut total_winnings = 0_i64;
 
-    for (index, hand) in sorted_hands.iter().enumerate() {
-        let bid = hands_to_bids.get(hand).unwrap();
+    for (index, (_, bid)) in sorted_hands_and_bids.iter().enumerate() {
         let rank = index + 1;
-        let amount_won = (rank as i32 * bid) as i64;
+        let amount_won = rank as i64 * **bid as i64;
         // println!("Total winning of {hand} is {amount_won} ({rank} * {bid})");
         total_winnings += amount_won;
     }
@@ -60,7 +71,7 @@ fn _solve(input: &String, compare_fn: &dyn Fn(&str, &str) -> i8) -> i64 {
 #[derive(PartialEq, PartialOrd, Debug)]
 // When derived on enums, variants are ordered by their discriminants.
 // By default, the discriminant is smallest for variants at the top, and largest for variants at the bottom. Here’s an example:
-enum HandType {
+pub enum HandType {
     HighCard,
     SinglePair,
     DoublePair,
@@ -70,7 +81,7 @@ enum HandType {
     FiveOfAKind,
 }
 
-fn compare_hands_part_1(hand_1: &str, hand_2: &str) -> i8 {
+pub fn compare_hands_part_1(hand_1: &str, hand_2: &str) -> i8 {
     // Returns -1 if hand_1 is stronger, 0 if equal, 1 if card_2 is stronger
     let hand_1_type = get_hand_type(hand_1);
     let hand_2_type = get_hand_type(hand_2);
@@ -97,12 +108,12 @@ fn compare_hands_part_1(hand_1: &str, hand_2: &str) -> i8 {
             }
         }
 
-        // We should not reach here, no cards should be identical.
-        panic!()
+        // Every card is identical, so are the hands.
+        return 0;
     }
 }
 
-fn compare_hands_part_2(hand_1: &str, hand_2: &str) -> i8 {
+pub fn compare_hands_part_2(hand_1: &str, hand_2: &str) -> i8 {
     // Returns -1 if hand_1 is stronger, 0 if equal, 1 if card_2 is stronger
 
     let upgraded_hand_1_type = try_get_best_hand_type(hand_1);
@@ -130,24 +141,18 @@ fn compare_hands_part_2(hand_1: &str, hand_2: &str) -> i8 {
             }
         }
 
-        // We should not reach here, no cards should be identical.
-        panic!()
+        // Every card is identical, so are the hands.
+        return 0;
     }
 }
 
-fn _find_keys_for_value<'a>(map: &'a HashMap<char, i8>, value: i8) -> Vec<char> {
+fn _find_keys_for_value(map: &HashMap<char, i8>, value: i8) -> Vec<char> {
     map.iter()
-        .filter_map(|(key, &val)| {
-            if val == value {
-                Some(key.clone())
-            } else {
-                None
-            }
-        })
+        .filter_map(|(key, &val)| if val == value { Some(*key) } else { None })
         .collect()
 }
 
-fn try_get_best_hand_type(hand: &str) -> HandType {
+pub fn try_get_best_hand_type(hand: &str) -> HandType {
     let current_type = get_hand_type(hand);
     if hand.chars().position(|card| card == 'J').is_none() {
         // There is no joker in the hand, we just return the standard
@@ -184,7 +189,7 @@ fn try_get_best_hand_type(hand: &str) -> HandType {
         };
     }
 }
-fn get_hand_type(hand: &str) -> HandType {
+pub fn get_hand_type(hand: &str) -> HandType {
     let hand_count = count_cards(hand);
 
     if get_five_of_kind_cards(&hand_count).is_some() {
@@ -316,8 +321,8 @@ fn get_four_of_kind_cards(hand: &HashMap<char, i8>) -> Option<(char, char)> {
         }
     }
 
-    if highest_card.is_some() && lowest_card.is_some() {
-        return Some((highest_card.unwrap(), lowest_card.unwrap()));
+    if let (Some(highest_card), Some(lowest_card)) = (highest_card, lowest_card) {
+        return Some((highest_card, lowest_card));
     }
     return None;
 }
@@ -333,8 +338,8 @@ fn get_full_house_cards(hand: &HashMap<char, i8>) -> Option<(char, char)> {
         }
     }
 
-    if highest_card.is_some() && lowest_card.is_some() {
-        return Some((highest_card.unwrap(), lowest_card.unwrap()));
+    if let (Some(highest_card), Some(lowest_card)) = (highest_card, lowest_card) {
+        return Some((highest_card, lowest_card));
     }
     return None;
 }
@@ -351,8 +356,8 @@ fn get_three_of_kind_cards(hand: &HashMap<char, i8>) -> Option<char> {
         }
     }
 
-    if highest_card.is_some() && lowest_card.is_none() {
-        return Some(highest_card.unwrap());
+    if let (Some(highest_card), None) = (highest_card, lowest_card) {
+        return Some(highest_card);
     }
     return None;
 }
@@ -368,8 +373,8 @@ fn get_double_pair_cards(hand: &HashMap<char, i8>) -> Option<(char, char)> {
         }
     }
 
-    if first_pair.is_some() && second_pair.is_some() {
-        return Some((first_pair.unwrap(), second_pair.unwrap()));
+    if let (Some(first_pair), Some(second_pair)) = (first_pair, second_pair) {
+        return Some((first_pair, second_pair));
     }
     return None;
 }
@@ -384,11 +389,68 @@ fn get_single_pair_cards(hand: &HashMap<char, i8>) -> Option<char> {
         }
     }
 
-    if pair.is_some() {
+    if let Some(pair) = pair {
         // It's only a pair
-        return Some(pair.unwrap());
+        return Some(pair);
     }
 
     // It's something else.
     return None;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    #[test]
+    fn solves_part_1_example() {
+        let camel_cards = CamelCards::parse(EXAMPLE).unwrap();
+        assert_eq!(camel_cards.part_1().unwrap(), Answer::from(6440));
+    }
+
+    #[test]
+    fn solves_part_2_example() {
+        let camel_cards = CamelCards::parse(EXAMPLE).unwrap();
+        assert_eq!(camel_cards.part_2().unwrap(), Answer::from(5905));
+    }
+
+    #[test]
+    fn finds_hand_types() {
+        assert_eq!(get_hand_type("32T3K"), HandType::SinglePair);
+        assert_eq!(get_hand_type("KK677"), HandType::DoublePair);
+        assert_eq!(get_hand_type("T55J5"), HandType::ThreeOfAKind);
+        assert_eq!(get_hand_type("23332"), HandType::FullHouse);
+        assert_eq!(get_hand_type("AA8AA"), HandType::FourOfAKind);
+        assert_eq!(get_hand_type("AAAAA"), HandType::FiveOfAKind);
+        assert_eq!(get_hand_type("23456"), HandType::HighCard);
+    }
+
+    #[test]
+    fn upgrades_hand_types_with_jokers() {
+        assert_eq!(try_get_best_hand_type("32T3K"), HandType::SinglePair);
+        assert_eq!(try_get_best_hand_type("KK677"), HandType::DoublePair);
+        assert_eq!(try_get_best_hand_type("T55J5"), HandType::FourOfAKind);
+        assert_eq!(try_get_best_hand_type("KTJJT"), HandType::FourOfAKind);
+        assert_eq!(try_get_best_hand_type("QQQJA"), HandType::FourOfAKind);
+        assert_eq!(try_get_best_hand_type("JJJJJ"), HandType::FiveOfAKind);
+        assert_eq!(try_get_best_hand_type("2345J"), HandType::SinglePair);
+        assert_eq!(try_get_best_hand_type("22JJ3"), HandType::FourOfAKind);
+        assert_eq!(try_get_best_hand_type("2233J"), HandType::FullHouse);
+    }
+
+    #[test]
+    fn breaks_ties_with_the_first_different_card() {
+        assert_eq!(compare_hands_part_1("33332", "2AAAA"), -1);
+        assert_eq!(compare_hands_part_1("KK677", "KTJJT"), -1);
+        // With jokers, J is the weakest card when breaking ties.
+        assert_eq!(compare_hands_part_2("JKKK2", "QQQQ2"), 1);
+        assert_eq!(compare_hands_part_2("QQQQ2", "QQQQ2"), 0);
+    }
+}
@@ -0,0 +1,132 @@
+use std::{fmt, io, str::FromStr};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input file could not be read.
+    Io { path: String, source: io::Error },
+    /// A line of an input streamed from a reader could not be read, e.g. it is not valid UTF-8.
+    ReadLine {
+        path: String,
+        line: usize,
+        source: io::Error,
+    },
+    /// The input does not follow the format of the puzzle.
+    Parse {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// The input is well-formed, but the puzzle cannot be solved from it.
+    Puzzle { day: u8, message: String },
+    /// There is no solution for that day yet.
+    NotImplemented { day: u8 },
+    /// The solution of that day panicked.
+    Panic { day: u8, message: String },
+    /// Some parts could not be solved when running several days at once.
+    Unsolved { failures: usize },
+    /// An input could not be downloaded.
+    Download { url: String, message: String },
+    /// The file of accepted answers is malformed.
+    Answers {
+        path: String,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(f, "{path} could not be read: {source}")
+            }
+            Error::ReadLine { path, line, source } => {
+                write!(f, "{path}, line {line} could not be read: {source}")
+            }
+            Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                message,
+            } => write!(
+                f,
+                "Day {day}, line {line}, column {column}: {message}, found {text:?}"
+            ),
+            Error::Puzzle { day, message } => write!(f, "Day {day}: {message}"),
+            Error::NotImplemented { day } => write!(f, "Day {day} is not implemented yet."),
+            Error::Panic { day, message } => write!(f, "Day {day} panicked: {message}"),
+            Error::Unsolved { failures } => write!(f, "{failures} run(s) failed."),
+            Error::Download { url, message } => write!(f, "Could not download {url}: {message}"),
+            Error::Answers {
+                path,
+                line,
+                message,
+            } => write!(f, "{path}, line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } | Error::ReadLine { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A line of a puzzle input, which knows where it comes from so that it can locate parse errors.
+#[derive(Debug, Clone, Copy)]
+pub struct InputLine<'a> {
+    pub day: u8,
+    /// Line number, starting at 1.
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> InputLine<'a> {
+    /// Error about `token`, which must be a slice of this line so that its column can be found.
+    pub fn error(&self, token: &str, message: &str) -> Error {
+        return Error::Parse {
+            day: self.day,
+            line: self.number,
+            column: self.column_of(token),
+            text: token.to_string(),
+            message: message.to_string(),
+        };
+    }
+
+    /// Parses `token`, a slice of this line, reporting `expected` if it is not valid.
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T> {
+        return token
+            .parse::<T>()
+            .map_err(|_| self.error(token, &format!("expected {expected}")));
+    }
+
+    fn column_of(&self, token: &str) -> usize {
+        // Tokens are slices of the line, so their offset is the distance between the two pointers.
+        // Anything else (e.g. an empty token past the end of the line) is reported at the end of the line.
+        let offset = (token.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+        if offset <= self.text.len() && self.text.is_char_boundary(offset) {
+            return self.text[..offset].chars().count() + 1;
+        }
+        return self.text.chars().count() + 1;
+    }
+}
+
+/// Numbered lines of a day's puzzle input.
+pub fn input_lines(day: u8, input: &str) -> impl Iterator<Item = InputLine<'_>> {
+    return input
+        .lines()
+        .enumerate()
+        .map(move |(index, text)| InputLine {
+            day,
+            number: index + 1,
+            text,
+        });
+}
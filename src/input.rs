@@ -0,0 +1,298 @@
+//! Puzzle inputs, kept in a local cache directory and downloaded when missing.
+//!
+//! Inputs are personal, so they are never committed: the cache directory is git-ignored and
+//! downloads need the session cookie of an Advent of Code account.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::{Error, Result};
+
+pub const DEFAULT_CACHE_DIR: &str = "inputs";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Environment variable holding the session cookie used to download inputs.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+/// Minimum time between two downloads, to stay gentle with the Advent of Code servers.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+const USER_AGENT: &str = "github.com/tcrasset/advent_of_code_2023";
+/// Longest a download may take, so that a stalled connection does not hang the run.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+/// File of the cache directory holding the time of the last download, in nanoseconds since the
+/// Unix epoch, so that the rate limit also holds across runs.
+const LAST_DOWNLOAD_FILE: &str = ".last_download";
+
+/// Something able to perform an HTTP GET request, so that downloads can be redirected in tests.
+pub trait HttpClient {
+    /// Body of the response to a GET of `url` with the `session` cookie, if it was successful.
+    fn get(&self, url: &str, session: &str) -> Result<String>;
+}
+
+/// Downloads through the `curl` command line tool, which handles TLS and proxies for us.
+pub struct CurlClient;
+
+impl HttpClient for CurlClient {
+    fn get(&self, url: &str, session: &str) -> Result<String> {
+        let download_error = |message: String| Error::Download {
+            url: url.to_string(),
+            message,
+        };
+
+        // The cookie is given on stdin rather than as an argument, where any local user could
+        // read it in the list of processes.
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--location"])
+            .args(["--user-agent", USER_AGENT])
+            .args(["--max-time", &DOWNLOAD_TIMEOUT.as_secs().to_string()])
+            .args(["--config", "-"])
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| download_error(format!("could not run curl: {error}")))?;
+        if let Some(mut stdin) = curl.stdin.take() {
+            stdin
+                .write_all(get_curl_config(session).as_bytes())
+                .map_err(|error| download_error(format!("could not configure curl: {error}")))?;
+        }
+        let output = curl
+            .wait_with_output()
+            .map_err(|error| download_error(format!("could not run curl: {error}")))?;
+
+        if !output.status.success() {
+            return Err(download_error(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        return String::from_utf8(output.stdout)
+            .map_err(|_| download_error("the response is not valid UTF-8".to_string()));
+    }
+}
+
+/// `curl` configuration sending the `session` cookie, as a quoted string of the config file syntax.
+fn get_curl_config(session: &str) -> String {
+    let mut quoted = String::new();
+    for character in format!("session={session}").chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            character => quoted.push(character),
+        }
+    }
+    return format!("cookie = \"{quoted}\"\n");
+}
+
+/// Resolves the input of a day from the cache, downloading it first if it is missing and a
+/// session is available.
+pub struct InputManager<C: HttpClient> {
+    pub cache_dir: PathBuf,
+    pub base_url: String,
+    pub session: Option<String>,
+    pub min_interval: Duration,
+    pub client: C,
+}
+
+impl InputManager<CurlClient> {
+    /// Manager of the default cache, downloading with the session found in the environment.
+    pub fn from_env() -> InputManager<CurlClient> {
+        let mut manager = InputManager::new(DEFAULT_CACHE_DIR, CurlClient);
+        manager.session = std::env::var(SESSION_VARIABLE).ok();
+        return manager;
+    }
+}
+
+impl<C: HttpClient> InputManager<C> {
+    pub fn new(cache_dir: impl Into<PathBuf>, client: C) -> InputManager<C> {
+        return InputManager {
+            cache_dir: cache_dir.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+            min_interval: DEFAULT_MIN_INTERVAL,
+            client,
+        };
+    }
+
+    /// Where the input of `day` is cached.
+    pub fn cached_path(&self, day: u8) -> PathBuf {
+        return cached_path(&self.cache_dir, day);
+    }
+
+    /// Makes sure the input of `day` is cached and returns its path.
+    ///
+    /// An input that is already cached is never downloaded again.
+    pub fn fetch(&self, day: u8) -> Result<PathBuf> {
+        let path = self.cached_path(day);
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let Some(session) = &self.session else {
+            return Err(Error::Download {
+                url: self.url(day),
+                message: format!(
+                    "{} is not cached, set {SESSION_VARIABLE} to download it",
+                    path.display()
+                ),
+            });
+        };
+
+        fs::create_dir_all(&self.cache_dir).map_err(|source| io_error(&self.cache_dir, source))?;
+        self.wait_for_rate_limit();
+        let input = self.client.get(&self.url(day), session);
+        self.record_download();
+        let input = input?;
+
+        // Writing then renaming means an interrupted download never leaves a partial input behind.
+        let partial_path = path.with_extension("part");
+        fs::write(&partial_path, input).map_err(|source| io_error(&partial_path, source))?;
+        fs::rename(&partial_path, &path).map_err(|source| io_error(&path, source))?;
+
+        return Ok(path);
+    }
+
+    /// Input of `day`, downloaded first if needed.
+    pub fn get(&self, day: u8) -> Result<String> {
+        let path = self.fetch(day)?;
+        return fs::read_to_string(&path).map_err(|source| io_error(&path, source));
+    }
+
+    fn url(&self, day: u8) -> String {
+        return format!(
+            "{}/2023/day/{day}/input",
+            self.base_url.trim_end_matches('/')
+        );
+    }
+
+    fn wait_for_rate_limit(&self) {
+        let Ok(last_download) = fs::read_to_string(self.cache_dir.join(LAST_DOWNLOAD_FILE)) else {
+            return;
+        };
+        let Ok(last_download) = last_download.trim().parse::<u64>() else {
+            return;
+        };
+
+        let last_download = UNIX_EPOCH + Duration::from_nanos(last_download);
+        if let Ok(elapsed) = SystemTime::now().duration_since(last_download) {
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+    }
+
+    fn record_download(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        // Losing the time of the last download only loosens the rate limit, it is not worth failing for.
+        let _ = fs::write(self.cache_dir.join(LAST_DOWNLOAD_FILE), now.to_string());
+    }
+}
+
+/// Where the input of `day` is cached in `cache_dir`.
+pub fn cached_path(cache_dir: impl AsRef<Path>, day: u8) -> PathBuf {
+    return cache_dir.as_ref().join(format!("day_{day}.txt"));
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> Error {
+    return Error::Io {
+        path: path.display().to_string(),
+        source,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, time::Instant};
+
+    use super::*;
+
+    /// Stand-in for the Advent of Code server, recording every request it receives.
+    struct FakeServer {
+        requests: RefCell<Vec<String>>,
+    }
+
+    impl HttpClient for &FakeServer {
+        fn get(&self, url: &str, session: &str) -> Result<String> {
+            self.requests.borrow_mut().push(url.to_string());
+            return Ok(format!("input for {session}\n"));
+        }
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let cache_dir =
+            std::env::temp_dir().join(format!("aoc_2023_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        return cache_dir;
+    }
+
+    #[test]
+    fn downloads_missing_inputs_once() {
+        let server = FakeServer {
+            requests: RefCell::new(Vec::new()),
+        };
+        let mut manager = InputManager::new(cache_dir("download"), &server);
+        manager.base_url = "http://127.0.0.1:8080/".to_string();
+        manager.session = Some("secret".to_string());
+        manager.min_interval = Duration::ZERO;
+
+        assert_eq!(manager.get(7).unwrap(), "input for secret\n");
+        assert_eq!(manager.get(7).unwrap(), "input for secret\n");
+        assert_eq!(
+            *server.requests.borrow(),
+            vec!["http://127.0.0.1:8080/2023/day/7/input".to_string()]
+        );
+
+        fs::remove_dir_all(&manager.cache_dir).unwrap();
+    }
+
+    #[test]
+    fn needs_a_session_to_download() {
+        let server = FakeServer {
+            requests: RefCell::new(Vec::new()),
+        };
+        let manager = InputManager::new(cache_dir("no_session"), &server);
+
+        assert!(matches!(manager.fetch(1), Err(Error::Download { .. })));
+        assert!(server.requests.borrow().is_empty());
+    }
+
+    #[test]
+    fn quotes_the_session_for_curl() {
+        assert_eq!(
+            get_curl_config("53616c74"),
+            "cookie = \"session=53616c74\"\n"
+        );
+        assert_eq!(
+            get_curl_config("a\"b\\c\nd"),
+            "cookie = \"session=a\\\"b\\\\c\\nd\"\n"
+        );
+    }
+
+    #[test]
+    fn waits_between_downloads() {
+        let server = FakeServer {
+            requests: RefCell::new(Vec::new()),
+        };
+        let mut manager = InputManager::new(cache_dir("rate_limit"), &server);
+        manager.session = Some("secret".to_string());
+        manager.min_interval = Duration::from_millis(200);
+
+        let start = Instant::now();
+        manager.fetch(1).unwrap();
+        manager.fetch(2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        fs::remove_dir_all(&manager.cache_dir).unwrap();
+    }
+}
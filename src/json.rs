@@ -0,0 +1,102 @@
+//! Just enough JSON to write flat records for scripts and dashboards.
+
+use std::fmt::{self, Display};
+
+/// A JSON object whose fields keep the order in which they were added.
+#[derive(Debug, Clone, Default)]
+pub struct JsonObject {
+    fields: Vec<(String, String)>,
+}
+
+impl JsonObject {
+    pub fn new() -> JsonObject {
+        return JsonObject::default();
+    }
+
+    pub fn string(self, key: &str, value: &str) -> JsonObject {
+        return self.raw(key, escape(value));
+    }
+
+    pub fn number(self, key: &str, value: impl Display) -> JsonObject {
+        return self.raw(key, value.to_string());
+    }
+
+    pub fn null(self, key: &str) -> JsonObject {
+        return self.raw(key, "null".to_string());
+    }
+
+    /// Adds a field whose value is already valid JSON, e.g. a nested object or an array.
+    pub fn raw(mut self, key: &str, json: String) -> JsonObject {
+        self.fields.push((key.to_string(), json));
+        return self;
+    }
+}
+
+impl Display for JsonObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (index, (key, value)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}:{}", escape(key), value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Quotes and escapes `value` as a JSON string.
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped.push('"');
+    return escaped;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_quotes_and_backslashes() {
+        assert_eq!(escape(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(escape(r"C:\inputs"), r#""C:\\inputs""#);
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        assert_eq!(escape("a\nb\rc\td"), r#""a\nb\rc\td""#);
+        assert_eq!(escape("\u{0}\u{1b}\u{1f}"), r#""\u0000\u001b\u001f""#);
+    }
+
+    #[test]
+    fn keeps_other_characters() {
+        assert_eq!(escape(""), r#""""#);
+        assert_eq!(escape("día 5 → 35"), r#""día 5 → 35""#);
+    }
+
+    #[test]
+    fn writes_fields_in_order() {
+        let record = JsonObject::new()
+            .number("day", 5)
+            .null("part")
+            .string("error", "line 3: \"x\"");
+        assert_eq!(
+            record.to_string(),
+            r#"{"day":5,"part":null,"error":"line 3: \"x\""}"#
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Solutions to the Advent of Code 2023 puzzles.
+//!
+//! Every day lives in its own module and implements [`solver::Solver`],
+//! the days are registered in [`solver::DAYS`] so they can all be driven the same way.
+
+pub mod answers;
+pub mod bench;
+pub mod day_1;
+pub mod day_2;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod error;
+pub mod input;
+pub mod json;
+mod random;
+pub mod runner;
+pub mod solver;
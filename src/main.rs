@@ -1,12 +1,603 @@
-// mod day_1;
-// mod day_2;
-// mod day_5;
-// mod day_6;
-mod day_7;
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    process,
+};
+
+use advent_of_code_2023::{
+    answers::DEFAULT_ANSWERS_FILE,
+    bench,
+    day_1::{self, CalibrationDocument, DigitScanner, Vocabulary},
+    day_2::{self, Bag, ColorDistribution, Configuration, GameGenerator},
+    day_5,
+    error::{Error, Result},
+    input::{InputManager, DEFAULT_BASE_URL, DEFAULT_CACHE_DIR},
+    runner::{self, Format},
+    solver::{self, Solver},
+};
+use clap::{Parser, Subcommand};
+use regex::Regex;
+
+#[derive(Parser)]
+#[command(about = "Solutions to the Advent of Code 2023 puzzles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve the puzzle of a given day.
+    Run {
+        /// Day of the puzzle to solve.
+        #[arg(short, long, required_unless_present = "all")]
+        day: Option<u8>,
+
+        /// Solve every implemented day from its default input, and print a summary table.
+        #[arg(long, conflicts_with_all = ["day", "input"])]
+        all: bool,
+
+        /// Only solve this part of the puzzle (both parts are solved by default).
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Puzzle input, defaults to `inputs/day_<DAY>.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Output format: `text`, or `json` for one record per day and part.
+        #[arg(short, long, default_value = "text")]
+        format: Format,
+
+        /// Spelled-out digits of day 1: `english`, `french`, `german`, or a file of `<word> = <digit>` lines.
+        #[arg(long, conflicts_with = "all", value_parser = parse_vocabulary)]
+        vocabulary: Option<Vocabulary>,
+    },
+    /// Check the answers of every implemented day against the accepted ones.
+    Verify {
+        /// Only check this day.
+        #[arg(short, long)]
+        day: Option<u8>,
+
+        /// File of accepted answers, in the format of `answers.example.toml`.
+        #[arg(short, long, default_value = DEFAULT_ANSWERS_FILE)]
+        answers: String,
+    },
+    /// Time reading, parsing and both parts of every implemented day.
+    Bench {
+        /// Only time this day.
+        #[arg(short, long)]
+        day: Option<u8>,
+
+        /// Puzzle input, defaults to `inputs/day_<DAY>.txt`.
+        #[arg(short, long, requires = "day")]
+        input: Option<String>,
+
+        /// Number of times each stage is run.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+    },
+    /// Show how each line of a day 1 document adds to the calibration sum.
+    Explain {
+        /// Part of the puzzle whose digits are matched: 1 for digits only, 2 for spelled-out digits too.
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// Puzzle input, defaults to `inputs/day_1.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Only explain the lines matching this regular expression.
+        #[arg(short, long, value_parser = parse_filter)]
+        filter: Option<Regex>,
+
+        /// Spelled-out digits of part 2: `english`, `french`, `german`, or a file of `<word> = <digit>` lines.
+        #[arg(long, default_value = "english", value_parser = parse_vocabulary)]
+        vocabulary: Vocabulary,
+    },
+    /// Stream a day 1 document of any size and print its calibration sum.
+    Calibrate {
+        /// Part of the puzzle whose digits are matched: 1 for digits only, 2 for spelled-out digits too.
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// Document to read, `-` for the standard input, e.g. `zcat document.gz | ... calibrate -i -`.
+        #[arg(short, long, default_value = "-")]
+        input: String,
+
+        /// Spelled-out digits of part 2: `english`, `french`, `german`, or a file of `<word> = <digit>` lines.
+        #[arg(long, default_value = "english", value_parser = parse_vocabulary)]
+        vocabulary: Vocabulary,
+    },
+    /// Find the day 2 games that are possible with a given bag, or the smallest bag for enough games.
+    Bag {
+        /// Cubes in the bag, e.g. `12 red, 13 green, 14 blue`; there is no cube of the other colors.
+        #[arg(short, long, required_unless_present_any = ["at_most", "smallest_for"])]
+        bag: Option<Configuration>,
+
+        /// Only limit the listed colors, e.g. `10 blue`; any number of the other cubes is allowed.
+        #[arg(short, long, conflicts_with = "bag")]
+        at_most: Option<Configuration>,
+
+        /// Find the bag with the fewest cubes making at least this fraction of the games possible, e.g. `0.9`.
+        #[arg(short, long, conflicts_with_all = ["bag", "at_most"], value_parser = parse_fraction)]
+        smallest_for: Option<f64>,
+
+        /// Puzzle input, defaults to `inputs/day_2.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Report which day 2 games are possible, and for the others which draws exceed the bag.
+    Report {
+        /// Cubes in the bag, defaults to the one of part 1: `12 red, 13 green, 14 blue`.
+        #[arg(short, long)]
+        bag: Option<Configuration>,
+
+        /// Puzzle input, defaults to `inputs/day_2.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Output format: `text` for a table, or `json` for one record per game.
+        #[arg(short, long, default_value = "text")]
+        format: Format,
+    },
+    /// Print random day 2 games in the format of the puzzle input.
+    Generate {
+        /// Number of games.
+        #[arg(short = 'n', long, default_value_t = 100)]
+        games: usize,
+
+        /// Smallest and largest number of draws of a game, e.g. `1..6`.
+        #[arg(short, long, default_value = "1..6", value_parser = parse_draws)]
+        draws: (usize, usize),
+
+        /// Colors and the distribution of their numbers of cubes in a draw, either uniform
+        /// (`red=0..20`) or geometric with a given mean (`red~4.5`).
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            default_value = "red=0..20,green=0..20,blue=0..20"
+        )]
+        colors: Vec<ColorDistribution>,
+
+        /// Seed of the random generator, the same seed always generates the same games.
+        #[arg(short, long, default_value_t = 2023)]
+        seed: u64,
+    },
+    /// Print statistics about the cubes of each color of day 2 games.
+    Summary {
+        /// Puzzle input, defaults to `inputs/day_2.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Print the day 5 almanac maps composed into a single map, from seeds to locations by default.
+    Compose {
+        /// Category to convert from.
+        #[arg(short, long, default_value = "seed")]
+        from: String,
+
+        /// Category to convert to.
+        #[arg(short, long, default_value = "location")]
+        to: String,
+
+        /// Puzzle input, defaults to `inputs/day_5.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Convert a number of a day 5 category into another category, e.g. the fertilizer of seed 79.
+    Convert {
+        /// Category of the number, e.g. `seed`.
+        #[arg(short, long)]
+        from: String,
+
+        /// Number to convert.
+        #[arg(short, long)]
+        number: i64,
+
+        /// Category to convert to, e.g. `fertilizer`.
+        #[arg(short, long)]
+        to: String,
+
+        /// Puzzle input, defaults to `inputs/day_5.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Find the day 5 seeds that end up at a location, or in a range of locations.
+    Seeds {
+        /// Location, or first location of the range.
+        #[arg(short, long)]
+        location: i64,
+
+        /// End of the range of locations, excluded; only `location` is looked up by default.
+        #[arg(short, long)]
+        until: Option<i64>,
+
+        /// Puzzle input, defaults to `inputs/day_5.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Check a day 5 almanac for overlapping, empty or unreachable maps, negative or overflowing numbers, and an unpaired seed.
+    Lint {
+        /// Puzzle input, defaults to `inputs/day_5.txt`.
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Download the inputs that are not cached yet.
+    Fetch {
+        /// Only fetch this day, every implemented day is fetched by default.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+
+        /// Session cookie of your Advent of Code account, read from `AOC_SESSION` by default.
+        #[arg(short, long)]
+        session: Option<String>,
+
+        /// Directory where the inputs are cached.
+        #[arg(short, long, default_value = DEFAULT_CACHE_DIR)]
+        cache_dir: String,
+
+        /// Server to download from, e.g. a local stand-in.
+        #[arg(short, long, default_value = DEFAULT_BASE_URL)]
+        base_url: String,
+    },
+}
+
 fn main() {
-    // day_1::solve("inputs/day_1.txt".to_string());
-    // day_2::solve("inputs/day_2.txt".to_string());
-    // day_5::solve("inputs/day_5.txt".to_string());
-    // day_6::solve("inputs/day_6.txt".to_string());
-    day_7::solve("inputs/day_7.txt".to_string());
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Run {
+            day,
+            all,
+            part,
+            input,
+            format,
+            vocabulary,
+        } => {
+            let parts = match part {
+                Some(part) => vec![part],
+                None => vec![1, 2],
+            };
+            match (day, vocabulary) {
+                (Some(1), Some(vocabulary)) => {
+                    let input_file = input.unwrap_or(runner::default_input_file(1));
+                    let parse = |input: &str| -> Result<Box<dyn Solver>> {
+                        return Ok(Box::new(CalibrationDocument {
+                            input: input.to_string(),
+                            vocabulary: vocabulary.clone(),
+                        }));
+                    };
+                    runner::run_with_parser(1, parse, &parts, &input_file, format)
+                }
+                (Some(day), Some(_)) => Err(Error::Puzzle {
+                    day,
+                    message: "only day 1 has spelled-out digits, --vocabulary does not apply"
+                        .to_string(),
+                }),
+                (Some(day), None) if !all => {
+                    let input_file = input.unwrap_or(runner::default_input_file(day));
+                    runner::run(day, &parts, &input_file, format)
+                }
+                _ => {
+                    let days: Vec<_> = solver::DAYS
+                        .iter()
+                        .map(|day| (day, runner::default_input_file(day.number)))
+                        .collect();
+                    runner::run_all(&days, &parts, format)
+                }
+            }
+        }
+        Command::Verify { day, answers } => verify(day, &answers),
+        Command::Bench {
+            day,
+            input,
+            iterations,
+        } => bench(day, input, iterations),
+        Command::Explain {
+            part,
+            input,
+            filter,
+            vocabulary,
+        } => explain(part, input, filter, &vocabulary),
+        Command::Calibrate {
+            part,
+            input,
+            vocabulary,
+        } => calibrate(part, &input, &vocabulary),
+        Command::Bag {
+            bag,
+            at_most,
+            smallest_for,
+            input,
+        } => {
+            let query = match (bag, at_most) {
+                (Some(bag), _) => Some(Bag::Exact(bag)),
+                (_, Some(at_most)) => Some(Bag::AtMost(at_most)),
+                _ => None,
+            };
+            find_bag(query, smallest_for, input)
+        }
+        Command::Report { bag, input, format } => report(bag, input, format),
+        Command::Generate {
+            games,
+            draws,
+            colors,
+            seed,
+        } => {
+            let generator = GameGenerator {
+                number_of_games: games,
+                draws_per_game: draws,
+                colors,
+                seed,
+            };
+            generator.generate().map(|games| {
+                for game in games {
+                    println!("{game}");
+                }
+            })
+        }
+        Command::Summary { input } => summary(input),
+        Command::Compose { from, to, input } => compose(&from, &to, input),
+        Command::Convert {
+            from,
+            number,
+            to,
+            input,
+        } => convert(&from, number, &to, input),
+        Command::Seeds {
+            location,
+            until,
+            input,
+        } => seeds(location, until, input),
+        Command::Lint { input } => lint(input),
+        Command::Fetch {
+            day,
+            session,
+            cache_dir,
+            base_url,
+        } => fetch(day, session, cache_dir, base_url),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+fn verify(day: Option<u8>, answers_file: &str) -> Result<()> {
+    let days = match day {
+        Some(day) => vec![solver::get_day(day)?],
+        None => solver::DAYS.iter().collect(),
+    };
+    return runner::verify(&days, &[1, 2], answers_file);
+}
+
+fn bench(day: Option<u8>, input: Option<String>, iterations: usize) -> Result<()> {
+    let days = match day {
+        Some(day) => {
+            let input_file = input.unwrap_or(runner::default_input_file(day));
+            vec![(solver::get_day(day)?, input_file)]
+        }
+        None => solver::DAYS
+            .iter()
+            .map(|day| (day, runner::default_input_file(day.number)))
+            .collect(),
+    };
+    return bench::run_bench(&days, iterations);
+}
+
+fn explain(
+    part: u8,
+    input: Option<String>,
+    filter: Option<Regex>,
+    vocabulary: &Vocabulary,
+) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(1));
+    let input = runner::read_input(&input_file)?;
+    let scanner = get_day_1_scanner(part, vocabulary);
+
+    let mut total_sum: u64 = 0;
+    for explanation in day_1::get_explained_lines(&input, &scanner, filter.as_ref()) {
+        println!("{explanation}");
+        total_sum += explanation.value() as u64;
+    }
+    println!("Sum of the explained lines: {total_sum}");
+    return Ok(());
+}
+
+fn calibrate(part: u8, input_file: &str, vocabulary: &Vocabulary) -> Result<()> {
+    let scanner = get_day_1_scanner(part, vocabulary);
+    let total_sum = if input_file == "-" {
+        day_1::get_streamed_sum(io::stdin().lock(), "the standard input", &scanner)?
+    } else {
+        let file = File::open(input_file).map_err(|source| Error::Io {
+            path: input_file.to_string(),
+            source,
+        })?;
+        day_1::get_streamed_sum(BufReader::new(file), input_file, &scanner)?
+    };
+    println!("{total_sum}");
+    return Ok(());
+}
+
+fn find_bag(query: Option<Bag>, smallest_for: Option<f64>, input: Option<String>) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(2));
+    let games = day_2::get_games(&runner::read_input(&input_file)?)?;
+
+    let bag = match (query, smallest_for) {
+        (Some(bag), _) => bag,
+        (None, fraction) => {
+            let bag = day_2::get_smallest_bag(&games, fraction.unwrap_or(1.0))?;
+            let total_cubes: i64 = bag.colors().map(|(_, number)| number as i64).sum();
+            println!("Smallest bag: {bag} ({total_cubes} cubes)");
+            Bag::Exact(bag)
+        }
+    };
+
+    let possible_games = day_2::get_possible_games(&games, &bag);
+    let identifiers: Vec<String> = possible_games
+        .iter()
+        .map(|game| game.id.to_string())
+        .collect();
+    let identifier_sum: i64 = possible_games.iter().map(|game| game.id as i64).sum();
+    println!(
+        "Possible games ({} of {}): {}",
+        possible_games.len(),
+        games.len(),
+        identifiers.join(", ")
+    );
+    println!("Sum of their identifiers: {identifier_sum}");
+    return Ok(());
+}
+
+fn report(bag: Option<Configuration>, input: Option<String>, format: Format) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(2));
+    let games = day_2::get_games(&runner::read_input(&input_file)?)?;
+    let bag = bag.unwrap_or_else(day_2::get_current_configuration);
+    let game_reports = day_2::get_game_reports(&games, &bag);
+
+    if format == Format::Json {
+        for game_report in game_reports.iter() {
+            println!("{}", game_report.to_json());
+        }
+        return Ok(());
+    }
+
+    print!("{}", day_2::get_game_reports_table(&game_reports));
+    return Ok(());
+}
+
+fn summary(input: Option<String>) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(2));
+    let games = day_2::get_games(&runner::read_input(&input_file)?)?;
+    if games.is_empty() {
+        println!("There is no game.");
+        return Ok(());
+    }
+
+    print!("{}", day_2::get_summary_table(&day_2::get_summary(&games)));
+    return Ok(());
+}
+
+fn compose(from: &str, to: &str, input: Option<String>) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(5));
+    let maps = day_5::get_maps(&runner::read_input(&input_file)?)?;
+    let path = day_5::get_path(&maps, from, to)?;
+
+    // Printed like a section of the almanac, which it could replace.
+    println!("{from}-to-{to} map:");
+    for element in day_5::get_composed_map_through_path(&path) {
+        println!("{element}");
+    }
+    return Ok(());
+}
+
+fn convert(from: &str, number: i64, to: &str, input: Option<String>) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(5));
+    let maps = day_5::get_maps(&runner::read_input(&input_file)?)?;
+    println!(
+        "{}",
+        day_5::get_number_in_category(&maps, from, number, to)?
+    );
+    return Ok(());
+}
+
+fn seeds(location: i64, until: Option<i64>, input: Option<String>) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(5));
+    let maps = day_5::get_maps(&runner::read_input(&input_file)?)?;
+
+    let until = match until {
+        Some(until) => until,
+        None => location.checked_add(1).ok_or_else(|| Error::Puzzle {
+            day: 5,
+            message: format!(
+                "the range of locations from {location} ends past the last 64-bit number"
+            ),
+        })?,
+    };
+    let location_range = location..until;
+    for seed_range in day_5::get_seed_ranges_from_location_range(&maps, location_range)? {
+        println!("{}..{}", seed_range.start, seed_range.end);
+    }
+    return Ok(());
+}
+
+fn lint(input: Option<String>) -> Result<()> {
+    let input_file = input.unwrap_or(runner::default_input_file(5));
+    let lints = day_5::get_lints(&runner::read_input(&input_file)?)?;
+
+    if lints.is_empty() {
+        println!("No problem found.");
+    }
+    for lint in lints.iter() {
+        println!("{input_file}: {lint}");
+    }
+
+    let errors = lints.iter().filter(|lint| lint.kind.is_error()).count();
+    if errors > 0 {
+        return Err(Error::Puzzle {
+            day: 5,
+            message: format!("{errors} problem(s) found in {input_file}."),
+        });
+    }
+    return Ok(());
+}
+
+fn parse_draws(draws: &str) -> std::result::Result<(usize, usize), String> {
+    let expected = format!("expected `<min>..<max>`, found {draws:?}");
+    let (min, max) = draws.split_once("..").ok_or(&expected)?;
+    let min = min.trim().parse::<usize>().map_err(|_| &expected)?;
+    let max = max.trim().parse::<usize>().map_err(|_| &expected)?;
+    if min == 0 || min > max {
+        return Err(format!("expected 1 <= min <= max, found {draws:?}"));
+    }
+    return Ok((min, max));
+}
+
+/// Scanner of the digits of the given part of day 1, part 2 also matching the words of `vocabulary`.
+fn get_day_1_scanner(part: u8, vocabulary: &Vocabulary) -> DigitScanner {
+    return match part {
+        1 => DigitScanner::new(&Vocabulary::new([])),
+        _ => DigitScanner::new(vocabulary),
+    };
+}
+
+fn parse_vocabulary(name_or_file: &str) -> std::result::Result<Vocabulary, String> {
+    return Vocabulary::load(name_or_file).map_err(|error| error.to_string());
+}
+
+fn parse_fraction(fraction: &str) -> std::result::Result<f64, String> {
+    return match fraction.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+        _ => Err(format!(
+            "expected a number between 0 and 1, found {fraction:?}"
+        )),
+    };
+}
+
+fn parse_filter(filter: &str) -> std::result::Result<Regex, String> {
+    return Regex::new(filter).map_err(|error| error.to_string());
+}
+
+fn fetch(
+    day: Option<u8>,
+    session: Option<String>,
+    cache_dir: String,
+    base_url: String,
+) -> Result<()> {
+    let mut manager = InputManager::from_env();
+    manager.cache_dir = cache_dir.into();
+    manager.base_url = base_url;
+    manager.session = session.or(manager.session);
+
+    let days = match day {
+        Some(day) => vec![day],
+        None => solver::DAYS.iter().map(|day| day.number).collect(),
+    };
+    for day in days {
+        let path = manager.fetch(day)?;
+        println!("Day {day}: {}", path.display());
+    }
+    return Ok(());
 }
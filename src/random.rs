@@ -0,0 +1,28 @@
+//! SplitMix64, a small generator that is good enough for test inputs and needs no dependency.
+
+/// Generator of pseudo-random numbers, the same seed always gives the same numbers.
+pub(crate) struct Random(u64);
+
+impl Random {
+    pub(crate) fn new(seed: u64) -> Random {
+        return Random(seed);
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    /// Number between `min` and `max`, included.
+    pub(crate) fn next_between(&mut self, min: u64, max: u64) -> u64 {
+        return min + self.next() % (max - min + 1);
+    }
+
+    /// Number in `[0, 1)`.
+    pub(crate) fn next_fraction(&mut self) -> f64 {
+        return (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+}
@@ -0,0 +1,411 @@
+use std::{
+    any::Any,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::Answers,
+    error::{Error, Result},
+    input::{self, DEFAULT_CACHE_DIR},
+    json::JsonObject,
+    solver::{self, Answer, Day, Solver},
+};
+
+/// How the results of a run are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable text.
+    Text,
+    /// One JSON record per line, for each day and part.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> std::result::Result<Self, Self::Err> {
+        return match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("expected text or json, found {format:?}")),
+        };
+    }
+}
+
+/// Outcome of solving one part of a day.
+pub struct PartRun {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Result<Answer>,
+    /// Wall-clock time spent solving the part, parsing excluded.
+    pub duration: Duration,
+    /// Hash of the input the part was solved from, see [`hash_input`].
+    pub input_hash: u64,
+}
+
+impl PartRun {
+    pub fn to_json(&self) -> JsonObject {
+        let record = JsonObject::new()
+            .number("day", self.day)
+            .number("part", self.part);
+        let record = match &self.answer {
+            Ok(answer) => record.string("answer", &answer.to_string()).null("error"),
+            Err(error) => record.null("answer").string("error", &error.to_string()),
+        };
+        return record
+            .number("duration_ns", self.duration.as_nanos())
+            .string("input_hash", &format!("{:016x}", self.input_hash));
+    }
+}
+
+/// Where the input of a day is expected when none is given.
+pub fn default_input_file(day: u8) -> String {
+    return input::cached_path(DEFAULT_CACHE_DIR, day)
+        .display()
+        .to_string();
+}
+
+/// Solves the given parts of a day's puzzle and prints their results.
+pub fn run(day: u8, parts: &[u8], input_file: &str, format: Format) -> Result<()> {
+    let runs = solver::get_day(day).and_then(|day| solve_day(day, parts, input_file));
+    return print_runs(day, runs, format);
+}
+
+/// Like [`run`], but the input is parsed by `parse` instead of the registered parser of the day,
+/// e.g. to solve day 1 with another vocabulary.
+pub fn run_with_parser(
+    day: u8,
+    parse: impl Fn(&str) -> Result<Box<dyn Solver>>,
+    parts: &[u8],
+    input_file: &str,
+    format: Format,
+) -> Result<()> {
+    let runs = solve_with_parser(day, parse, parts, input_file);
+    return print_runs(day, runs, format);
+}
+
+fn print_runs(day: u8, runs: Result<Vec<PartRun>>, format: Format) -> Result<()> {
+    let runs = match runs {
+        Ok(runs) => runs,
+        Err(error) => {
+            if format == Format::Json {
+                println!("{}", get_day_failure_json(day, &error));
+            }
+            return Err(error);
+        }
+    };
+
+    if format == Format::Json {
+        for run in runs.iter() {
+            println!("{}", run.to_json());
+        }
+    } else {
+        println!("### Solving Day {day} puzzle...###");
+        for run in runs.iter() {
+            if let Ok(answer) = &run.answer {
+                println!("Part {} Result: {answer}", run.part);
+            }
+        }
+    }
+
+    // The first failure is the one reported, like it would have stopped the run.
+    return match runs.into_iter().find_map(|run| run.answer.err()) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    };
+}
+
+/// Solves the given parts of the given days from their input file, and prints a summary table.
+///
+/// Every day is solved even if another one fails, the failures are reported once all days are done.
+pub fn run_all(days: &[(&Day, String)], parts: &[u8], format: Format) -> Result<()> {
+    if format == Format::Json {
+        return run_all_as_json(days, parts);
+    }
+
+    let mut failures: Vec<Error> = Vec::new();
+
+    println!(
+        "{:>3} | {:>4} | {:<20} | {:>12}",
+        "Day", "Part", "Answer", "Time"
+    );
+    println!("{:-<3}-+-{:-<4}-+-{:-<20}-+-{:-<12}", "", "", "", "");
+    for (day, input_file) in days {
+        let runs = match solve_day(day, parts, input_file) {
+            Ok(runs) => runs,
+            Err(error) => {
+                println!(
+                    "{:>3} | {:>4} | {:<20} | {:>12}",
+                    day.number, "-", "FAILED", "-"
+                );
+                failures.push(error);
+                continue;
+            }
+        };
+
+        for run in runs {
+            let answer = match run.answer {
+                Ok(answer) => answer.to_string(),
+                Err(error) => {
+                    failures.push(error);
+                    "FAILED".to_string()
+                }
+            };
+            let duration = format!("{:.3?}", run.duration);
+            println!(
+                "{:>3} | {:>4} | {:<20} | {:>12}",
+                run.day, run.part, answer, duration
+            );
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!();
+    for failure in failures.iter() {
+        eprintln!("{failure}");
+    }
+    return Err(Error::Unsolved {
+        failures: failures.len(),
+    });
+}
+
+/// Record of a day whose input could not be read or parsed, the failure is then recorded for the
+/// day as a whole.
+fn get_day_failure_json(day: u8, error: &Error) -> JsonObject {
+    return JsonObject::new()
+        .number("day", day)
+        .null("part")
+        .null("answer")
+        .string("error", &error.to_string());
+}
+
+fn run_all_as_json(days: &[(&Day, String)], parts: &[u8]) -> Result<()> {
+    let mut failures = 0;
+    for (day, input_file) in days {
+        let runs = match solve_day(day, parts, input_file) {
+            Ok(runs) => runs,
+            Err(error) => {
+                println!("{}", get_day_failure_json(day.number, &error));
+                failures += 1;
+                continue;
+            }
+        };
+
+        for run in runs {
+            println!("{}", run.to_json());
+            if run.answer.is_err() {
+                failures += 1;
+            }
+        }
+    }
+
+    if failures == 0 {
+        return Ok(());
+    }
+    return Err(Error::Unsolved { failures });
+}
+
+/// Solves the given parts of the given days and compares them to the accepted answers.
+///
+/// Parts without an accepted answer are reported as missing, but only wrong answers and errors fail.
+pub fn verify(days: &[&Day], parts: &[u8], answers_file: &str) -> Result<()> {
+    let answers = Answers::load(answers_file)?;
+    let mut wrong_answers = 0;
+    let mut failures: Vec<Error> = Vec::new();
+
+    println!(
+        "{:>3} | {:>4} | {:<7} | {:<20} | Expected",
+        "Day", "Part", "Status", "Answer"
+    );
+    println!(
+        "{:-<3}-+-{:-<4}-+-{:-<7}-+-{:-<20}-+-{:-<8}",
+        "", "", "", "", ""
+    );
+    for day in days {
+        let runs = match solve_day(day, parts, &default_input_file(day.number)) {
+            Ok(runs) => runs,
+            Err(error) => {
+                println!(
+                    "{:>3} | {:>4} | {:<7} | {:<20} |",
+                    day.number, "-", "ERROR", ""
+                );
+                failures.push(error);
+                continue;
+            }
+        };
+
+        for run in runs {
+            let expected = answers.get(run.day, run.part);
+            let (status, answer) = match (run.answer, expected) {
+                (Err(error), _) => {
+                    failures.push(error);
+                    ("ERROR", String::new())
+                }
+                (Ok(answer), None) => ("missing", answer.to_string()),
+                (Ok(answer), Some(expected)) if answer.to_string() == expected => {
+                    ("pass", answer.to_string())
+                }
+                (Ok(answer), Some(_)) => {
+                    wrong_answers += 1;
+                    ("FAIL", answer.to_string())
+                }
+            };
+            println!(
+                "{:>3} | {:>4} | {:<7} | {:<20} | {}",
+                run.day,
+                run.part,
+                status,
+                answer,
+                expected.unwrap_or_default()
+            );
+        }
+    }
+
+    if wrong_answers == 0 && failures.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!();
+    for failure in failures.iter() {
+        eprintln!("{failure}");
+    }
+    return Err(Error::Unsolved {
+        failures: wrong_answers + failures.len(),
+    });
+}
+
+/// Solves the given parts of a day, catching panics so that they can be reported like any other error.
+///
+/// Reading or parsing the input fails the whole day, solving a part only fails that part.
+pub fn solve_day(day: &Day, parts: &[u8], input_file: &str) -> Result<Vec<PartRun>> {
+    return solve_with_parser(day.number, day.parse, parts, input_file);
+}
+
+fn solve_with_parser(
+    day: u8,
+    parse: impl Fn(&str) -> Result<Box<dyn Solver>>,
+    parts: &[u8],
+    input_file: &str,
+) -> Result<Vec<PartRun>> {
+    let input = read_input(input_file)?;
+    let input_hash = hash_input(&input);
+    let solver: Box<dyn Solver> = catch_panic(day, || parse(&input))?;
+
+    let mut runs = Vec::new();
+    for part in parts {
+        let start = Instant::now();
+        let answer = catch_panic(day, || solver.solve_part(*part));
+        runs.push(PartRun {
+            day,
+            part: *part,
+            answer,
+            duration: start.elapsed(),
+            input_hash,
+        });
+    }
+    return Ok(runs);
+}
+
+/// Turns a panic of the solution of `day` into an error, so that other days can still be solved.
+pub fn catch_panic<T>(day: u8, solve: impl FnOnce() -> Result<T>) -> Result<T> {
+    return match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(result) => result,
+        Err(payload) => Err(Error::Panic {
+            day,
+            message: panic_message(payload),
+        }),
+    };
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    return "unknown panic".to_string();
+}
+
+/// 64-bit FNV-1a hash of the input, which unlike the standard library's hasher is stable across
+/// runs, versions and platforms, so that records of different runs can be compared.
+pub fn hash_input(input: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return hash;
+}
+
+pub fn read_input(input_file: &str) -> Result<String> {
+    return fs::read_to_string(input_file).map_err(|source| Error::Io {
+        path: input_file.to_string(),
+        source,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panicking_parse(_input: &str) -> Result<Box<dyn Solver>> {
+        panic!("this day does not parse anything");
+    }
+
+    const PANICKING_DAY: Day = Day {
+        number: 3,
+        parse: panicking_parse,
+    };
+
+    #[test]
+    fn fails_a_single_day_with_its_error() {
+        let result = run_with_parser(3, panicking_parse, &[1, 2], "Cargo.toml", Format::Json);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Day 3 panicked: this day does not parse anything"
+        );
+
+        let result = run(1, &[1, 2], "inputs/missing.txt", Format::Text);
+        assert!(matches!(result, Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn runs_the_other_days_after_a_failure() {
+        let days = [
+            (
+                solver::get_day(1).unwrap(),
+                "inputs/missing.txt".to_string(),
+            ),
+            (&PANICKING_DAY, "Cargo.toml".to_string()),
+        ];
+        for format in [Format::Text, Format::Json] {
+            let result = run_all(&days, &[1, 2], format);
+            assert!(matches!(result, Err(Error::Unsolved { failures: 2 })));
+        }
+    }
+
+    #[test]
+    fn fails_verification_when_a_day_fails() {
+        let result = verify(&[&PANICKING_DAY], &[1, 2], "answers.example.toml");
+        assert!(matches!(result, Err(Error::Unsolved { failures: 1 })));
+    }
+
+    #[test]
+    fn records_the_failure_of_a_whole_day() {
+        let error = Error::Panic {
+            day: 3,
+            message: "this day does not parse anything".to_string(),
+        };
+        assert_eq!(
+            get_day_failure_json(3, &error).to_string(),
+            r#"{"day":3,"part":null,"answer":null,"error":"Day 3 panicked: this day does not parse anything"}"#
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::{
+    day_1, day_2, day_5, day_6, day_7,
+    error::{Error, Result},
+};
+
+/// The answer to one part of a puzzle, as it would be submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer(String);
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer(value.to_string())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer(value.to_string())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer(value.to_string())
+    }
+}
+
+/// A puzzle of a given day.
+///
+/// The input is parsed once into the implementing type, both parts are then
+/// solved from that parsed input.
+pub trait Solver {
+    fn parse(input: &str) -> Result<Self>
+    where
+        Self: Sized;
+
+    fn part_1(&self) -> Result<Answer>;
+
+    fn part_2(&self) -> Result<Answer>;
+
+    fn solve_part(&self, part: u8) -> Result<Answer> {
+        match part {
+            1 => self.part_1(),
+            _ => self.part_2(),
+        }
+    }
+}
+
+/// A registered day, with the parser producing its `Solver`.
+pub struct Day {
+    pub number: u8,
+    pub parse: fn(&str) -> Result<Box<dyn Solver>>,
+}
+
+fn parse<S: Solver + 'static>(input: &str) -> Result<Box<dyn Solver>> {
+    return Ok(Box::new(S::parse(input)?));
+}
+
+/// Every day that has been implemented so far, in order.
+pub const DAYS: [Day; 5] = [
+    Day {
+        number: 1,
+        parse: parse::<day_1::CalibrationDocument>,
+    },
+    Day {
+        number: 2,
+        parse: parse::<day_2::CubeGames>,
+    },
+    Day {
+        number: 5,
+        parse: parse::<day_5::Almanac>,
+    },
+    Day {
+        number: 6,
+        parse: parse::<day_6::Races>,
+    },
+    Day {
+        number: 7,
+        parse: parse::<day_7::CamelCards>,
+    },
+];
+
+pub fn get_day(number: u8) -> Result<&'static Day> {
+    return DAYS
+        .iter()
+        .find(|day| day.number == number)
+        .ok_or(Error::NotImplemented { day: number });
+}